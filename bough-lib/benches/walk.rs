@@ -0,0 +1,59 @@
+//! Compares sequential and parallel walking.
+//!
+//! Walks a generated tree by default, or the directory given by `BOUGH_BENCH_PATH`. Run with
+//! `cargo bench -p bough-lib --features parallel`.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use bough_lib::file::FileTree;
+
+const RUNS: u32 = 10;
+
+fn main() {
+    let (path, generated) = match std::env::var_os("BOUGH_BENCH_PATH") {
+        Some(path) => (PathBuf::from(path), false),
+        None => (generate(), true),
+    };
+
+    let sequential = time(|| FileTree::new(&path).unwrap().build().unwrap());
+    let parallel = time(|| FileTree::new(&path).unwrap().build_parallel().unwrap());
+    let items = FileTree::new(&path).unwrap().build().unwrap().tree().len();
+
+    println!("walking {} ({items} items)", path.display());
+    println!("sequential: {sequential:?} per build");
+    println!("parallel:   {parallel:?} per build");
+
+    if generated {
+        fs::remove_dir_all(&path).unwrap();
+    }
+}
+
+/// The mean duration of a build over several runs, after one warm up run
+fn time(build: impl Fn() -> FileTree) -> Duration {
+    build();
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        std::hint::black_box(build());
+    }
+    start.elapsed() / RUNS
+}
+
+/// Generate a tree of 20 directories, each with 20 subdirectories of 25 files
+fn generate() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("bough-bench-{}", std::process::id()));
+
+    for a in 0..20 {
+        for b in 0..20 {
+            let dir = root.join(format!("dir{a}/sub{b}"));
+            fs::create_dir_all(&dir).unwrap();
+            for f in 0..25 {
+                fs::write(dir.join(format!("file{f}.txt")), "").unwrap();
+            }
+        }
+    }
+
+    root
+}
@@ -0,0 +1,810 @@
+//! Parsing of `.boughconf` files.
+//!
+//! A `.boughconf` lists descriptions for the entries of a tree, one per line, as the path of the
+//! entry relative to the root of the tree followed by a space and its description. Directories
+//! are written with a trailing `/`. Lines beginning with `#` are comments. Everything after a
+//! `---` line is a `key=value` default for the arguments of `bough`.
+//!
+//! Paths may also be globs. Globs without a `/` match the name of an item at any depth, while
+//! globs containing one match its whole relative path, where `*` does not match a `/` and `**`
+//! matches any number of directories. When several entries match an item, a path given exactly
+//! always wins over a glob, and otherwise the last matching glob wins. Entries without a
+//! description never hide the description of another entry.
+//!
+//! ```text
+//! # Directories have a trailing slash
+//! src/ The source code
+//! src/main.rs The entry point
+//! *.test.ts Tests
+//! Cargo.toml The package manifest
+//! ---
+//! depth=2
+//! icons=nerd
+//! ```
+//!
+//! Large projects may also give a `.boughconf` in any subdirectory, describing the items inside
+//! of it with paths relative to that directory. These are applied with [Config::apply_in] after
+//! the configurations of their parents, so the deepest description of an item wins. Defaults are
+//! only read from the root of the tree, and beneath them from the [user configuration](Config::user).
+//!
+//! [generate] writes a `.boughconf` for an existing tree, merging it into any existing file, and
+//! [Config::check] finds entries which have gone out of date.
+
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error as StdError,
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{
+    file::{FileTree, FileTreeItem, FileType, Id},
+    output::icons::IconType,
+    tree::filter::Pattern,
+};
+
+/// The name of the configuration file read from the root of a tree
+pub const CONFIG_FILE: &str = ".boughconf";
+
+/// The name of the user configuration file, read from `$XDG_CONFIG_HOME/bough/`
+pub const USER_CONFIG_FILE: &str = "config";
+
+/// The line which separates descriptions from defaults
+pub const SEPARATOR: &str = "---";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Reading the file failed
+    Io(io::ErrorKind),
+    /// A line could not be parsed
+    Syntax,
+    /// A default was given for an unknown key
+    UnknownKey,
+    /// A default had a value of the wrong type
+    InvalidValue,
+}
+
+#[derive(Debug)]
+pub struct Error {
+    pub kind: ErrorKind,
+    /// The line the error occurred on, starting from 1. This is 0 for errors which are not
+    /// caused by a specific line.
+    pub line: usize,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new<S: ToString>(kind: ErrorKind, line: usize, message: S) -> Self {
+        Self {
+            kind,
+            line,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::new(ErrorKind::Io(value.kind()), 0, value)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            0 => write!(f, "{}", self.message),
+            line => write!(f, "line {line}: {}", self.message),
+        }
+    }
+}
+
+impl StdError for Error {}
+
+/// The output format given by the `output` default
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum OutputKind {
+    Text,
+    Html,
+    Ansi,
+    Markdown,
+}
+
+impl FromStr for OutputKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" | "txt" | "ascii" => Ok(Self::Text),
+            "html" => Ok(Self::Html),
+            "ansi" => Ok(Self::Ansi),
+            "markdown" | "md" => Ok(Self::Markdown),
+            _ => Err(format!(
+                "invalid output '{s}', expected one of text, html, ansi or markdown"
+            )),
+        }
+    }
+}
+
+impl Display for OutputKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Text => "text",
+            Self::Html => "html",
+            Self::Ansi => "ansi",
+            Self::Markdown => "markdown",
+        })
+    }
+}
+
+/// Default values for the arguments of `bough`, given after the `---` separator. Keys which
+/// were not given are [None].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Defaults {
+    pub output: Option<OutputKind>,
+    /// The max depth, where 0 walks the whole tree
+    pub depth: Option<usize>,
+    pub icons: Option<IconType>,
+    /// A theme file. Themes are not supported yet, so this is only parsed.
+    pub theme: Option<PathBuf>,
+    /// Include hidden entries
+    pub hidden: Option<bool>,
+    /// Respect ignore files
+    pub ignore: Option<bool>,
+    /// Show the root of the tree
+    pub root: Option<bool>,
+    /// Read `.boughconf` files in subdirectories
+    pub nested: Option<bool>,
+}
+
+impl Defaults {
+    /// The defaults which are set, as `(key, value)` pairs
+    pub fn pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = Vec::new();
+        let mut push = |key, value: Option<String>| {
+            if let Some(value) = value {
+                pairs.push((key, value));
+            }
+        };
+
+        push("output", self.output.map(|o| o.to_string()));
+        push("depth", self.depth.map(|d| d.to_string()));
+        push("icons", self.icons.map(|i| i.to_string()));
+        push(
+            "theme",
+            self.theme.as_ref().map(|t| t.display().to_string()),
+        );
+        push("hidden", self.hidden.map(|h| h.to_string()));
+        push("ignore", self.ignore.map(|i| i.to_string()));
+        push("root", self.root.map(|r| r.to_string()));
+        push("nested", self.nested.map(|n| n.to_string()));
+
+        pairs
+    }
+
+    /// Fill in any defaults which are not set from those of a configuration with a lower
+    /// priority
+    pub fn fill(&mut self, other: &Defaults) {
+        self.output = self.output.or(other.output);
+        self.depth = self.depth.or(other.depth);
+        self.icons = self.icons.or(other.icons);
+        self.theme = self.theme.take().or_else(|| other.theme.clone());
+        self.hidden = self.hidden.or(other.hidden);
+        self.ignore = self.ignore.or(other.ignore);
+        self.root = self.root.or(other.root);
+        self.nested = self.nested.or(other.nested);
+    }
+
+    /// Set a default from a `key=value` line
+    fn set(&mut self, key: &str, value: &str, line: usize) -> Result<(), Error> {
+        match key {
+            "output" => self.output = Some(parse(value, line)?),
+            "depth" => self.depth = Some(parse(value, line)?),
+            "icons" => self.icons = Some(parse(value, line)?),
+            "theme" => self.theme = Some(PathBuf::from(value)),
+            "hidden" => self.hidden = Some(parse(value, line)?),
+            "ignore" => self.ignore = Some(parse(value, line)?),
+            "root" => self.root = Some(parse(value, line)?),
+            "nested" => self.nested = Some(parse(value, line)?),
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::UnknownKey,
+                    line,
+                    format!("unknown key '{key}'"),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse a typed default value
+fn parse<T: FromStr>(value: &str, line: usize) -> Result<T, Error>
+where
+    T::Err: Display,
+{
+    value.parse().map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidValue,
+            line,
+            format!("invalid value '{value}': {e}"),
+        )
+    })
+}
+
+/// A description of a single entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigEntry {
+    /// The path of the entry relative to the directory of the configuration, without any
+    /// trailing slash
+    pub path: PathBuf,
+    /// The entry was written with a trailing slash, so only matches directories
+    pub directory: bool,
+    /// The path is a glob rather than the path of a single item
+    pub glob: bool,
+    /// The description of the entry. Entries may be listed without one.
+    pub description: Option<String>,
+    /// The line the entry was given on, starting from 1
+    pub line: usize,
+}
+
+impl ConfigEntry {
+    /// Parse an entry from a line which is not blank or a comment
+    fn parse(line: &str, number: usize) -> Result<Self, Error> {
+        let (path, description) = match line.split_once(char::is_whitespace) {
+            Some((path, description)) => (path, Some(description.trim().to_string())),
+            None => (line, None),
+        };
+
+        let glob = path.contains(['*', '?', '[', '{']);
+        if glob {
+            Pattern::new(path).map_err(|e| {
+                Error::new(
+                    ErrorKind::Syntax,
+                    number,
+                    format!("invalid glob '{path}': {e}"),
+                )
+            })?;
+        }
+
+        Ok(Self {
+            path: PathBuf::from(path.trim_end_matches('/')),
+            directory: path.ends_with('/'),
+            glob,
+            description: description.filter(|d| !d.is_empty()),
+            line: number,
+        })
+    }
+
+    /// Compile the glob of the entry, or [None] if it is not a glob
+    fn pattern(&self) -> Option<Pattern> {
+        self.glob
+            .then(|| Pattern::new(&self.path.to_string_lossy()).ok())
+            .flatten()
+    }
+}
+
+/// The items of a tree matched by the entries of a [Config]
+struct Resolved<'a> {
+    /// The entry which gives the description of each item
+    descriptions: HashMap<Id, &'a ConfigEntry>,
+    /// Every item matched by an entry, with or without a description
+    listed: HashSet<Id>,
+    /// Whether each entry matched any item
+    matched: Vec<bool>,
+}
+
+/// A parsed `.boughconf`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// The entries in the order they were given
+    pub entries: Vec<ConfigEntry>,
+    pub defaults: Defaults,
+}
+
+impl Config {
+    /// Read a configuration file
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let contents = std::fs::read_to_string(path)?;
+        contents.parse()
+    }
+
+    /// Read the `.boughconf` in a directory, returning [None] if there is none
+    pub fn find<P: AsRef<Path>>(dir: P) -> Result<Option<Self>, Error> {
+        let path = dir.as_ref().join(CONFIG_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        Self::read(path).map(Some)
+    }
+
+    /// Read the user configuration from `$XDG_CONFIG_HOME/bough/config`, returning [None] if there
+    /// is none. Only its defaults are used, beneath those of the project.
+    pub fn user() -> Result<Option<Self>, Error> {
+        match user_path() {
+            Some(path) if path.is_file() => Self::read(path).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Attach descriptions to the matching items of a tree, returning the entries which did not
+    /// match any item. Entries with a trailing slash only match directories, and entries without
+    /// one only match files.
+    pub fn apply<'a>(&'a self, tree: &mut FileTree) -> Vec<&'a ConfigEntry> {
+        let root = tree.root_path().to_path_buf();
+        self.apply_in(tree, &root)
+    }
+
+    /// Attach descriptions to the matching items inside of a directory of a tree, as for a
+    /// `.boughconf` found in that directory. Descriptions already attached by other
+    /// configurations are replaced, so configurations for deeper directories should be applied
+    /// last.
+    pub fn apply_in<'a>(&'a self, tree: &mut FileTree, dir: &Path) -> Vec<&'a ConfigEntry> {
+        let resolved = self.resolve(tree, dir);
+
+        for (id, entry) in resolved.descriptions {
+            if let Some(item) = tree.get_mut(id) {
+                item.description = entry.description.clone();
+            }
+        }
+
+        self.entries
+            .iter()
+            .zip(resolved.matched)
+            .filter_map(|(entry, matched)| (!matched).then_some(entry))
+            .collect()
+    }
+
+    /// Match the entries against the items inside of a directory of a tree, following the
+    /// precedence of exact paths over globs and later globs over earlier ones
+    fn resolve(&self, tree: &FileTree, dir: &Path) -> Resolved<'_> {
+        let mut resolved = Resolved {
+            descriptions: HashMap::new(),
+            listed: HashSet::new(),
+            matched: vec![false; self.entries.len()],
+        };
+        let Some(base) = tree.id_of(dir) else {
+            return resolved;
+        };
+
+        for (i, entry) in self.entries.iter().enumerate().filter(|(_, e)| !e.glob) {
+            if let Some(id) = find(tree, dir, entry) {
+                resolved.matched[i] = true;
+                resolved.listed.insert(id);
+                if entry.description.is_some() {
+                    resolved.descriptions.insert(id, entry);
+                }
+            }
+        }
+
+        let globs = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| Some((i, e, e.pattern()?)))
+            .collect::<Vec<_>>();
+        if globs.is_empty() {
+            return resolved;
+        }
+
+        for (id, item, _, _) in tree.tree().descendants(base) {
+            let Ok(relative) = item.path.strip_prefix(dir) else {
+                continue;
+            };
+            if item.file_type == FileType::Truncated {
+                continue;
+            }
+
+            let is_dir = item.is_directory();
+            let mut description = None;
+            for (i, entry, pattern) in &globs {
+                if entry.directory == is_dir && pattern.matches(relative, &item.name, is_dir) {
+                    resolved.matched[*i] = true;
+                    resolved.listed.insert(id);
+                    if entry.description.is_some() {
+                        description = Some(*entry);
+                    }
+                }
+            }
+
+            if let Some(entry) = description {
+                resolved.descriptions.entry(id).or_insert(entry);
+            }
+        }
+
+        resolved
+    }
+
+    /// Compare the entries against a tree, returning any problems in the order of the lines they
+    /// were found on, followed by the items without a description in the order of the tree
+    pub fn check(&self, tree: &FileTree) -> Vec<Problem> {
+        let resolved = self.resolve(tree, tree.root_path());
+        let mut problems = self.check_in(tree, tree.root_path());
+
+        for (id, item, _, _) in tree.tree().pre_order().skip(1) {
+            if item.file_type != FileType::Truncated && !resolved.descriptions.contains_key(&id) {
+                problems.extend(undescribed_item(tree, item));
+            }
+        }
+
+        problems
+    }
+
+    /// Compare the entries against the items inside of a directory of a tree, as for a
+    /// `.boughconf` found in that directory. Unlike [Config::check], items without a description
+    /// are not included, as other configurations may describe them; see [undescribed].
+    pub fn check_in(&self, tree: &FileTree, dir: &Path) -> Vec<Problem> {
+        let resolved = self.resolve(tree, dir);
+        let mut problems = Vec::new();
+        let mut first_lines = HashMap::new();
+
+        for (entry, matched) in self.entries.iter().zip(&resolved.matched) {
+            if let Some(first) = first_lines.get(&(&entry.path, entry.directory)) {
+                problems.push(Problem::Duplicate {
+                    entry: entry.clone(),
+                    first: *first,
+                });
+                continue;
+            }
+            first_lines.insert((&entry.path, entry.directory), entry.line);
+
+            if !matched {
+                problems.push(Problem::Stale(entry.clone()));
+            }
+        }
+
+        problems
+    }
+}
+
+/// The path of the user configuration, or [None] if neither `$XDG_CONFIG_HOME` nor `$HOME` are
+/// set
+pub fn user_path() -> Option<PathBuf> {
+    let var = |name| {
+        std::env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    let base = var("XDG_CONFIG_HOME").or_else(|| var("HOME").map(|h| h.join(".config")))?;
+
+    Some(base.join("bough").join(USER_CONFIG_FILE))
+}
+
+/// The items of a tree which have no description, once every configuration for it has been
+/// applied
+pub fn undescribed(tree: &FileTree) -> Vec<Problem> {
+    tree.tree()
+        .pre_order()
+        .skip(1)
+        .filter(|(_, item, _, _)| {
+            item.file_type != FileType::Truncated && item.description.is_none()
+        })
+        .filter_map(|(_, item, _, _)| undescribed_item(tree, item))
+        .collect()
+}
+
+/// An item without a description, or [None] if it is a configuration file itself, which is never
+/// expected to be described
+fn undescribed_item(tree: &FileTree, item: &FileTreeItem) -> Option<Problem> {
+    if is_config(item) {
+        return None;
+    }
+
+    Some(Problem::Undescribed {
+        path: PathBuf::from(relative_name(tree, &item.path)?),
+        directory: item.is_directory(),
+    })
+}
+
+/// Returns if an item is a `.boughconf` file
+fn is_config(item: &FileTreeItem) -> bool {
+    !item.is_directory() && item.name == CONFIG_FILE
+}
+
+/// Find the item an entry in a configuration for a directory describes
+fn find(tree: &FileTree, dir: &Path, entry: &ConfigEntry) -> Option<Id> {
+    tree.id_of(dir.join(&entry.path)).filter(|id| {
+        tree.tree()
+            .get_node(*id)
+            .is_some_and(|i| i.is_directory() == entry.directory)
+    })
+}
+
+/// The path of an item relative to the root of the tree, as it is written in a `.boughconf`
+fn relative_name(tree: &FileTree, path: &Path) -> Option<String> {
+    let path = path.strip_prefix(tree.root_path()).ok()?;
+    Some(
+        path.components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+    )
+}
+
+/// A problem found by [Config::check]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// An entry which does not match any item in the tree
+    Stale(ConfigEntry),
+    /// An item in the tree which no entry gives a description
+    Undescribed { path: PathBuf, directory: bool },
+    /// An entry for a path which an earlier line already gave
+    Duplicate {
+        entry: ConfigEntry,
+        /// The line of the first entry for the path
+        first: usize,
+    },
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let slash = |directory| if directory { "/" } else { "" };
+
+        match self {
+            Self::Stale(entry) => write!(
+                f,
+                "line {}: '{}{}' does not match anything in the tree",
+                entry.line,
+                entry.path.display(),
+                slash(entry.directory)
+            ),
+            Self::Undescribed { path, directory } => write!(
+                f,
+                "'{}{}' has no description",
+                path.display(),
+                slash(*directory)
+            ),
+            Self::Duplicate { entry, first } => write!(
+                f,
+                "line {}: '{}{}' was already given on line {first}",
+                entry.line,
+                entry.path.display(),
+                slash(entry.directory)
+            ),
+        }
+    }
+}
+
+impl FromStr for Config {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ret = Self::default();
+        let mut in_defaults = false;
+
+        for (i, line) in s.lines().enumerate() {
+            let number = i + 1;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line == SEPARATOR {
+                if in_defaults {
+                    return Err(Error::new(
+                        ErrorKind::Syntax,
+                        number,
+                        format!("'{SEPARATOR}' may only be given once"),
+                    ));
+                }
+                in_defaults = true;
+                continue;
+            }
+
+            if !in_defaults {
+                ret.entries.push(ConfigEntry::parse(line, number)?);
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(Error::new(
+                    ErrorKind::Syntax,
+                    number,
+                    format!("expected 'key=value', found '{line}'"),
+                ));
+            };
+            ret.defaults.set(key.trim(), value.trim(), number)?;
+        }
+
+        Ok(ret)
+    }
+}
+
+/// Generate the contents of a `.boughconf` for a tree, with a line for every item and the given
+/// defaults. The `---` separator is only written if there are defaults to follow it.
+///
+/// If the contents of an `existing` file are given, they are kept as they are. Items which none of
+/// its entries or globs match are added after its last entry with no description, and the defaults
+/// replace any it gives for the same keys. Items which already have a description, such as from a
+/// nested configuration, are left out, as are `.boughconf` files and items with whitespace in
+/// their path, which cannot be written.
+pub fn generate(
+    tree: &FileTree,
+    defaults: &Defaults,
+    existing: Option<&str>,
+) -> Result<String, Error> {
+    let existing = existing.unwrap_or_default();
+    let config: Config = existing.parse()?;
+    let listed = config.resolve(tree, tree.root_path()).listed;
+
+    let lines = existing.lines().collect::<Vec<_>>();
+    let separator = lines.iter().position(|l| l.trim() == SEPARATOR);
+    let (head, tail) = match separator {
+        Some(i) => (&lines[..i], &lines[i + 1..]),
+        None => (&lines[..], &[][..]),
+    };
+    // New entries go after the last existing one, so any comments below it stay at the end
+    let (entries, rest) = head.split_at(
+        head.iter()
+            .rposition(|l| !l.trim().is_empty() && !l.trim().starts_with('#'))
+            .map_or(head.len(), |i| i + 1),
+    );
+
+    let mut out = String::new();
+    for line in entries {
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    for (id, item, _, _) in tree.tree().pre_order().skip(1) {
+        let Some(name) = relative_name(tree, &item.path) else {
+            continue;
+        };
+
+        if item.file_type == FileType::Truncated
+            || is_config(item)
+            || name.contains(char::is_whitespace)
+            || listed.contains(&id)
+            || item.description.is_some()
+        {
+            continue;
+        }
+
+        out.push_str(&name);
+        if item.is_directory() {
+            out.push('/');
+        }
+        out.push('\n');
+    }
+
+    for line in rest {
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    let mut pairs = defaults.pairs();
+    if pairs.is_empty() && separator.is_none() {
+        return Ok(out);
+    }
+    if rest.is_empty() {
+        out.push('\n');
+    }
+    out.push_str(SEPARATOR);
+    out.push('\n');
+
+    for line in tail {
+        let key = line.split_once('=').map(|(k, _)| k.trim());
+        match pairs.iter().position(|(k, _)| Some(*k) == key) {
+            Some(i) => {
+                let (key, value) = pairs.remove(i);
+                out.push_str(&format!("{key}={value}\n"));
+            }
+            None => {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+    for (key, value) in pairs {
+        out.push_str(&format!("{key}={value}\n"));
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{Config, Defaults, ErrorKind, OutputKind};
+    use crate::output::icons::IconType;
+
+    #[test]
+    fn test_parse() {
+        let config: Config = "
+# A comment
+bough/ The bough binary project.
+Cargo.toml The workspace Cargo.toml file
+README.md
+
+---
+# Defaults
+output=txt
+depth = 2
+icons=nerd
+hidden=true
+"
+        .parse()
+        .unwrap();
+
+        assert_eq!(config.entries.len(), 3);
+        assert_eq!(config.entries[0].path, PathBuf::from("bough"));
+        assert!(config.entries[0].directory);
+        assert_eq!(
+            config.entries[0].description.as_deref(),
+            Some("The bough binary project.")
+        );
+        assert!(!config.entries[1].directory);
+        assert_eq!(config.entries[1].line, 4);
+        assert_eq!(config.entries[2].description, None);
+
+        assert_eq!(config.defaults.output, Some(OutputKind::Text));
+        assert_eq!(config.defaults.depth, Some(2));
+        assert_eq!(config.defaults.icons, Some(IconType::Nerd));
+        assert_eq!(config.defaults.hidden, Some(true));
+        assert_eq!(config.defaults.root, None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = "a.txt\n---\ndepth=many".parse::<Config>().unwrap_err();
+        assert_eq!((err.kind, err.line), (ErrorKind::InvalidValue, 3));
+        assert!(err.to_string().starts_with("line 3: "));
+
+        let err = "---\n\ncolour=red".parse::<Config>().unwrap_err();
+        assert_eq!((err.kind, err.line), (ErrorKind::UnknownKey, 3));
+
+        let err = "---\ndepth".parse::<Config>().unwrap_err();
+        assert_eq!((err.kind, err.line), (ErrorKind::Syntax, 2));
+
+        let err = "---\n---".parse::<Config>().unwrap_err();
+        assert_eq!((err.kind, err.line), (ErrorKind::Syntax, 2));
+
+        let err = "a.txt\nsrc/[a.rs".parse::<Config>().unwrap_err();
+        assert_eq!((err.kind, err.line), (ErrorKind::Syntax, 2));
+    }
+
+    #[test]
+    fn test_defaults_fill() {
+        let mut project = Defaults {
+            depth: Some(2),
+            ..Default::default()
+        };
+        project.fill(&Defaults {
+            depth: Some(5),
+            icons: Some(IconType::Nerd),
+            ..Default::default()
+        });
+
+        assert_eq!(project.depth, Some(2));
+        assert_eq!(project.icons, Some(IconType::Nerd));
+        assert_eq!(project.root, None);
+    }
+
+    #[test]
+    fn test_defaults_round_trip() {
+        let defaults = Defaults {
+            output: Some(OutputKind::Markdown),
+            depth: Some(3),
+            icons: Some(IconType::Emoji),
+            theme: None,
+            hidden: Some(false),
+            ignore: Some(true),
+            root: Some(true),
+            nested: None,
+        };
+        let text = defaults
+            .pairs()
+            .into_iter()
+            .map(|(k, v)| format!("{k}={v}\n"))
+            .collect::<String>();
+        let config: Config = format!("---\n{text}").parse().unwrap();
+
+        assert_eq!(config.defaults, defaults);
+    }
+}
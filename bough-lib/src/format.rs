@@ -1,14 +1,90 @@
 #[cfg(feature = "ansi")]
-mod ansi;
-mod text;
+pub mod ansi;
+#[cfg(feature = "html")]
+pub mod html;
+#[cfg(feature = "md")]
+pub mod md;
+pub mod text;
+
+use std::{error::Error as StdError, fmt::Display, io};
+
+use crate::output::{borders::BorderStyle, borders::BorderWeight, icons::IconType};
 
 pub trait TreeFormatItem {}
 
+/// An output format for a tree of type `T`, configured by `C`.
+///
+/// Formats write their output as they go rather than building the whole diagram in memory, so
+/// they can be streamed to files, pipes, or buffers.
 pub trait TreeFormat<T, C> {
-    fn display(&self, tree: &T, config: &C);
+    /// Render the tree into the given writer
+    fn render(&self, tree: &T, config: &C, out: &mut dyn io::Write) -> Result<(), Error>;
+
+    /// Render the tree into a [String]
+    fn to_string(&self, tree: &T, config: &C) -> Result<String, Error> {
+        let mut buf = Vec::new();
+        self.render(tree, config, &mut buf)?;
+        String::from_utf8(buf).map_err(|e| Error::new(ErrorKind::Encoding, e))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Writing the output failed
+    Io(io::ErrorKind),
+    /// The output was not valid UTF-8
+    Encoding,
+}
+
+#[derive(Debug)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new<S: ToString>(kind: ErrorKind, message: S) -> Self {
+        Self {
+            kind,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::new(ErrorKind::Io(value.kind()), value)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.kind, self.message)
+    }
 }
 
+impl StdError for Error {}
+
+/// Configuration shared between all of the output formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct FormatConfig {
-    hidden: bool,
-    git: bool,
+    /// The style of icons to display next to each item
+    pub icons: IconType,
+    /// Whether to show the root folder at the top of the tree
+    pub root: bool,
+    /// The line style used to draw the tree guides
+    pub style: BorderStyle,
+    /// Whether to end the tree with the numbers of directories and files, as `tree` does
+    pub report: bool,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            icons: IconType::None,
+            root: false,
+            style: BorderStyle::Solid(BorderWeight::Light),
+            report: false,
+        }
+    }
 }
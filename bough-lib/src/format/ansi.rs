@@ -1,3 +1,191 @@
-pub struct AnsiColors {}
+//! Terminal output using ANSI colour codes.
+//!
+//! [AnsiFormat] shares its layout with [TextFormat], so the two always produce the same structure
+//! and only differ in colouring.
 
-pub struct Colors {}
+use std::collections::HashMap;
+use std::io::{self, IsTerminal};
+
+use owo_colors::{OwoColorize, Style};
+
+use crate::{
+    file::{FileTree, FileTreeItem, FileType},
+    format::{
+        Error, FormatConfig, TreeFormat,
+        text::{Section, TextFormat, layout, report, section},
+    },
+};
+
+/// When colour codes should be emitted
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorMode {
+    /// Colour only if stdout is a terminal and `NO_COLOR` is not set
+    #[default]
+    Auto,
+    /// Always colour the output
+    Always,
+    /// Never colour the output
+    Never,
+}
+
+impl ColorMode {
+    /// Resolve whether colour should be used
+    pub fn enabled(&self) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => {
+                let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+                !no_color && io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+/// The styles applied to each part of the tree
+#[derive(Debug, Clone)]
+pub struct Colors {
+    pub directory: Style,
+    pub mount_point: Style,
+    pub executable: Style,
+    /// Named pipes
+    pub fifo: Style,
+    pub socket: Style,
+    /// Block and character devices
+    pub device: Style,
+    pub symlink: Style,
+    /// Symlinks whose target does not exist
+    pub broken: Style,
+    pub file: Style,
+    /// The tree guides
+    pub guides: Style,
+    /// Descriptions
+    pub comment: Style,
+    /// Errors which occurred while reading items
+    pub error: Style,
+    /// Styles for files by extension. These take precedence over `file`.
+    pub extensions: HashMap<String, Style>,
+}
+
+impl Default for Colors {
+    fn default() -> Self {
+        let mut extensions = HashMap::new();
+        let groups: [(&[&str], Style); 5] = [
+            (
+                &["rs", "c", "cpp", "go", "py", "js", "ts", "lua"],
+                Style::new().green(),
+            ),
+            (&["md", "txt", "rst"], Style::new().yellow()),
+            (
+                &["toml", "json", "yaml", "yml", "ini"],
+                Style::new().magenta(),
+            ),
+            (
+                &["png", "jpg", "jpeg", "gif", "svg"],
+                Style::new().bright_magenta(),
+            ),
+            (&["zip", "tar", "gz", "xz", "7z"], Style::new().red()),
+        ];
+
+        for (exts, style) in groups {
+            for ext in exts {
+                extensions.insert(ext.to_string(), style);
+            }
+        }
+
+        Self {
+            directory: Style::new().blue().bold(),
+            mount_point: Style::new().blue().bold().underline(),
+            executable: Style::new().green().bold(),
+            fifo: Style::new().yellow(),
+            socket: Style::new().magenta().bold(),
+            device: Style::new().yellow().bold(),
+            symlink: Style::new().cyan(),
+            broken: Style::new().red(),
+            file: Style::new(),
+            guides: Style::new().dimmed(),
+            comment: Style::new().dimmed().italic(),
+            error: Style::new().red().bold(),
+            extensions,
+        }
+    }
+}
+
+impl Colors {
+    /// Get the style for the name of an item
+    pub fn item_style(&self, item: &FileTreeItem) -> Style {
+        match item.file_type {
+            FileType::Truncated => self.guides,
+            FileType::Symlink { broken: true, .. } => self.broken,
+            FileType::Symlink { .. } => self.symlink,
+            FileType::Directory => self.directory,
+            FileType::MountPoint => self.mount_point,
+            FileType::Executable => self.executable,
+            FileType::Fifo => self.fifo,
+            FileType::Socket => self.socket,
+            FileType::BlockDevice | FileType::CharDevice => self.device,
+            FileType::File => item
+                .ext
+                .as_ref()
+                .and_then(|e| self.extensions.get(e))
+                .copied()
+                .unwrap_or(self.file),
+        }
+    }
+}
+
+/// A plain text tree coloured with ANSI escape codes
+#[derive(Default)]
+pub struct AnsiFormat {
+    /// The sections of each line, as in [TextFormat]
+    pub text: TextFormat,
+    pub colors: Colors,
+    pub mode: ColorMode,
+}
+
+impl AnsiFormat {
+    fn line(&self, item: &FileTreeItem, config: &FormatConfig) -> String {
+        self.text
+            .sections
+            .iter()
+            .filter_map(|s| {
+                let text = section(*s, item, config)?;
+                Some(match s {
+                    Section::Name => text.style(self.colors.item_style(item)).to_string(),
+                    Section::Comment => text.style(self.colors.comment).to_string(),
+                    Section::Error => text.style(self.colors.error).to_string(),
+                    _ => text,
+                })
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl TreeFormat<FileTree, FormatConfig> for AnsiFormat {
+    fn render(
+        &self,
+        tree: &FileTree,
+        config: &FormatConfig,
+        out: &mut dyn io::Write,
+    ) -> Result<(), Error> {
+        if !self.mode.enabled() {
+            return self.text.render(tree, config, out);
+        }
+
+        layout(tree, config, |row| {
+            writeln!(
+                out,
+                "{}{}",
+                row.guides.style(self.colors.guides),
+                self.line(row.item, config)
+            )
+        })?;
+
+        if config.report {
+            writeln!(out, "\n{}", report(tree))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+//! HTML output using nested `<details>` elements, so directories can be collapsed.
+//!
+//! Every element is given a `bough-` prefixed class so the output can be themed. Items get a
+//! class for their [FileType] and files additionally get a class for their extension, e.g.
+//! `bough-file bough-ext-rs`.
+
+use std::io;
+
+use crate::{
+    TreeItem,
+    file::{FileTree, FileTreeItem, FileType},
+    format::{
+        Error, FormatConfig, TreeFormat,
+        text::{display_name, report},
+    },
+};
+
+/// How descriptions are shown
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DescriptionStyle {
+    /// As a `title` tooltip on the item name
+    Tooltip,
+    /// As a span following the item name
+    #[default]
+    Span,
+}
+
+/// An HTML tree
+#[derive(Debug, Default, Clone)]
+pub struct HtmlFormat {
+    /// Output a complete document with a default stylesheet rather than a fragment
+    pub document: bool,
+    /// Whether directories start expanded
+    pub open: bool,
+    pub descriptions: DescriptionStyle,
+}
+
+static STYLESHEET: &str = "
+.bough-tree, .bough-tree ul { list-style: none; margin: 0; padding-left: 1.25em; }
+.bough-tree { font-family: monospace; padding-left: 0; }
+.bough-tree summary { cursor: pointer; }
+.bough-icon { margin-right: 0.4em; }
+.bough-dir > details > summary .bough-name { font-weight: bold; color: #3b6fd4; }
+.bough-mount > details > summary .bough-name { text-decoration: underline; }
+.bough-exec > .bough-name { font-weight: bold; color: #2e9e3e; }
+.bough-fifo > .bough-name { color: #b08800; }
+.bough-socket > .bough-name { font-weight: bold; color: #a03cb4; }
+.bough-device > .bough-name { font-weight: bold; color: #b08800; }
+.bough-link > .bough-name, .bough-link > details > summary .bough-name { color: #1a9c9c; }
+.bough-broken > .bough-name { color: #c83232; }
+.bough-target { color: #777; }
+.bough-error { margin-left: 1em; color: #c83232; }
+.bough-report { font-family: monospace; color: #777; }
+.bough-desc { margin-left: 1em; color: #777; font-style: italic; }
+";
+
+impl HtmlFormat {
+    fn write_item(
+        &self,
+        tree: &FileTree,
+        item: &FileTreeItem,
+        config: &FormatConfig,
+        out: &mut dyn io::Write,
+        depth: usize,
+    ) -> io::Result<()> {
+        let indent = "  ".repeat(depth);
+        write!(out, "{indent}<li class=\"{}\">", classes(item))?;
+
+        // Followed symlinks to directories have children and are rendered like directories
+        if item.file_type.is_dir() || !item.children().is_empty() {
+            let open = if self.open { " open" } else { "" };
+            write!(out, "<details{open}><summary>")?;
+            self.write_label(item, config, out)?;
+            writeln!(out, "</summary>")?;
+            self.write_children(tree, item, config, out, depth + 1)?;
+            writeln!(out, "{indent}</details></li>")
+        } else {
+            self.write_label(item, config, out)?;
+            writeln!(out, "</li>")
+        }
+    }
+
+    fn write_children(
+        &self,
+        tree: &FileTree,
+        item: &FileTreeItem,
+        config: &FormatConfig,
+        out: &mut dyn io::Write,
+        depth: usize,
+    ) -> io::Result<()> {
+        let indent = "  ".repeat(depth);
+        writeln!(out, "{indent}<ul>")?;
+        for child in item.children() {
+            if let Some(c) = tree.tree().get_node(*child) {
+                self.write_item(tree, c, config, out, depth + 1)?;
+            }
+        }
+        writeln!(out, "{indent}</ul>")
+    }
+
+    fn write_label(
+        &self,
+        item: &FileTreeItem,
+        config: &FormatConfig,
+        out: &mut dyn io::Write,
+    ) -> io::Result<()> {
+        if let Some(icon) = config.icons.icon(&item.file_type, item.ext.as_deref()) {
+            write!(out, "<span class=\"bough-icon\">{icon}</span>")?;
+        }
+
+        let name = escape(&display_name(item));
+        match (&item.description, self.descriptions) {
+            (Some(desc), DescriptionStyle::Tooltip) => write!(
+                out,
+                "<span class=\"bough-name\" title=\"{}\">{name}</span>",
+                escape(desc)
+            )?,
+            _ => write!(out, "<span class=\"bough-name\">{name}</span>")?,
+        }
+
+        if let FileType::Symlink { target, .. } = &item.file_type {
+            write!(
+                out,
+                "<span class=\"bough-target\"> -&gt; {}</span>",
+                escape(&target.display().to_string())
+            )?;
+        }
+
+        if let Some(error) = item.error {
+            write!(out, "<span class=\"bough-error\">[{error}]</span>")?;
+        }
+
+        if let (Some(desc), DescriptionStyle::Span) = (&item.description, self.descriptions) {
+            write!(out, "<span class=\"bough-desc\">{}</span>", escape(desc))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TreeFormat<FileTree, FormatConfig> for HtmlFormat {
+    fn render(
+        &self,
+        tree: &FileTree,
+        config: &FormatConfig,
+        out: &mut dyn io::Write,
+    ) -> Result<(), Error> {
+        let arena = tree.tree();
+        let Some(root) = arena.get_node(arena.root) else {
+            return Ok(());
+        };
+
+        if self.document {
+            writeln!(out, "<!DOCTYPE html>")?;
+            writeln!(out, "<html>")?;
+            writeln!(out, "<head>")?;
+            writeln!(out, "<meta charset=\"utf-8\">")?;
+            writeln!(out, "<title>{}</title>", escape(&display_name(root)))?;
+            writeln!(out, "<style>{STYLESHEET}</style>")?;
+            writeln!(out, "</head>")?;
+            writeln!(out, "<body>")?;
+        }
+
+        writeln!(out, "<ul class=\"bough-tree\">")?;
+        if config.root {
+            self.write_item(tree, root, config, out, 1)?;
+        } else {
+            for child in root.children() {
+                if let Some(c) = arena.get_node(*child) {
+                    self.write_item(tree, c, config, out, 1)?;
+                }
+            }
+        }
+        writeln!(out, "</ul>")?;
+
+        if config.report {
+            writeln!(out, "<p class=\"bough-report\">{}</p>", report(tree))?;
+        }
+
+        if self.document {
+            writeln!(out, "</body>")?;
+            writeln!(out, "</html>")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The CSS classes for an item
+fn classes(item: &FileTreeItem) -> String {
+    match &item.file_type {
+        FileType::Directory => String::from("bough-dir"),
+        FileType::MountPoint => String::from("bough-dir bough-mount"),
+        FileType::Executable => String::from("bough-file bough-exec"),
+        FileType::Fifo => String::from("bough-fifo"),
+        FileType::Socket => String::from("bough-socket"),
+        FileType::BlockDevice => String::from("bough-device bough-block"),
+        FileType::CharDevice => String::from("bough-device bough-char"),
+        FileType::Symlink { broken: true, .. } => String::from("bough-link bough-broken"),
+        FileType::Symlink { .. } if !item.children().is_empty() => {
+            String::from("bough-link bough-dir")
+        }
+        FileType::Symlink { .. } => String::from("bough-link"),
+        FileType::Truncated => String::from("bough-truncated"),
+        FileType::File => match &item.ext {
+            Some(ext) => format!("bough-file bough-ext-{}", class_name(ext)),
+            None => String::from("bough-file"),
+        },
+    }
+}
+
+/// Reduce a string to characters which are valid in a CSS class name
+fn class_name(s: &str) -> String {
+    s.chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Escape text for use in HTML content or attribute values
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,138 @@
+//! Markdown output, intended for READMEs and other project documentation.
+
+use std::io;
+use std::path::Path;
+
+use crate::{
+    file::{FileTree, FileTreeItem, FileType},
+    format::{
+        Error, FormatConfig, TreeFormat,
+        text::{TextFormat, label, layout, report},
+    },
+};
+
+/// How the tree is written in Markdown
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MarkdownStyle {
+    /// A fenced code block containing the plain text tree
+    #[default]
+    CodeBlock,
+    /// A nested bullet list
+    List,
+}
+
+/// A Markdown tree
+#[derive(Default)]
+pub struct MarkdownFormat {
+    pub style: MarkdownStyle,
+    /// The sections used by [MarkdownStyle::CodeBlock]
+    pub text: TextFormat,
+    /// Link each entry to its path relative to the root of the tree. Only used by
+    /// [MarkdownStyle::List], as links cannot be placed inside code blocks.
+    pub links: bool,
+}
+
+impl MarkdownFormat {
+    fn write_list(
+        &self,
+        tree: &FileTree,
+        config: &FormatConfig,
+        out: &mut dyn io::Write,
+    ) -> io::Result<()> {
+        // Without the root the top level items are at depth 1, but should not be indented
+        let base = if config.root { 0 } else { 1 };
+
+        layout(tree, config, |row| {
+            let indent = "  ".repeat(row.depth - base);
+            write!(out, "{indent}- ")?;
+
+            if let Some(icon) = config
+                .icons
+                .icon(&row.item.file_type, row.item.ext.as_deref())
+            {
+                write!(out, "{icon} ")?;
+            }
+
+            let name = code(&label(row.item));
+            if row.item.file_type == FileType::Truncated {
+                write!(out, "{}", row.item.name)?;
+            } else if self.links {
+                write!(out, "[{name}]({})", link(tree.root_path(), row.item))?;
+            } else {
+                write!(out, "{name}")?;
+            }
+
+            if let Some(error) = row.item.error {
+                write!(out, " [{error}]")?;
+            }
+
+            if let Some(desc) = &row.item.description {
+                write!(out, " — {desc}")?;
+            }
+
+            writeln!(out)
+        })
+    }
+}
+
+impl TreeFormat<FileTree, FormatConfig> for MarkdownFormat {
+    fn render(
+        &self,
+        tree: &FileTree,
+        config: &FormatConfig,
+        out: &mut dyn io::Write,
+    ) -> Result<(), Error> {
+        match self.style {
+            MarkdownStyle::CodeBlock => {
+                writeln!(out, "```")?;
+                self.text.render(tree, config, out)?;
+                writeln!(out, "```")?;
+            }
+            MarkdownStyle::List => {
+                self.write_list(tree, config, out)?;
+                if config.report {
+                    writeln!(out, "\n{}", report(tree))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Wrap text in an inline code span, using a longer fence if the text contains backticks
+fn code(s: &str) -> String {
+    if s.contains('`') {
+        format!("`` {s} ``")
+    } else {
+        format!("`{s}`")
+    }
+}
+
+/// Build a percent encoded link to an item relative to the root of the tree
+fn link(root: &Path, item: &FileTreeItem) -> String {
+    let relative = item.path.strip_prefix(root).unwrap_or(&item.path);
+    let parts = relative
+        .components()
+        .map(|c| percent_encode(&c.as_os_str().to_string_lossy()))
+        .collect::<Vec<_>>();
+
+    if parts.is_empty() {
+        String::from(".")
+    } else {
+        parts.join("/")
+    }
+}
+
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{b:02X}")),
+        }
+    }
+    out
+}
@@ -1,21 +1,38 @@
+use std::io;
+use std::time::SystemTime;
+
 use crate::{
-    file::{FileTree, FileTreeItem},
-    format::TreeFormat,
+    file::{FileTree, FileTreeItem, FileType},
+    format::{Error, FormatConfig, TreeFormat},
+    metadata::relative_time,
+    output::borders::TreeChars,
+    summary::counts,
 };
 
+/// A section of a rendered line. Sections are rendered in the order given to [TextFormat].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Section {
+    /// The icon of the item, see [IconType](crate::output::icons::IconType)
     Icon,
+    /// The name of the item
     Name,
+    /// The error which occurred while reading the item, such as `[permission denied]`
+    Error,
+    /// The time the item was last modified, relative to now
     Times,
+    /// A summary of a directory's contents, see [FileTree::summarize]
     Contents,
+    /// The description of the item
     Comment,
+    /// The size of the item in a human readable form
+    Size,
+    /// The permissions of the item, as printed by `ls -l`
+    Permissions,
+    /// The owning user and group of the item
+    Owner,
 }
 
-pub struct Config {
-    hidden: bool,
-    git: bool,
-}
-
+/// A plain text tree, as printed by `tree`
 pub struct TextFormat {
     pub sections: Vec<Section>,
 }
@@ -26,6 +43,7 @@ impl Default for TextFormat {
             sections: vec![
                 Section::Icon,
                 Section::Name,
+                Section::Error,
                 Section::Comment,
                 Section::Contents,
             ],
@@ -33,6 +51,145 @@ impl Default for TextFormat {
     }
 }
 
-impl TreeFormat<FileTree, Config> for TextFormat {
-    fn display(&self, tree: &FileTree, config: &Config) {}
+impl TextFormat {
+    /// Render the sections of a single item, skipping any which are empty
+    pub(crate) fn line(&self, item: &FileTreeItem, config: &FormatConfig) -> String {
+        self.sections
+            .iter()
+            .filter_map(|s| section(*s, item, config))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl TreeFormat<FileTree, FormatConfig> for TextFormat {
+    fn render(
+        &self,
+        tree: &FileTree,
+        config: &FormatConfig,
+        out: &mut dyn io::Write,
+    ) -> Result<(), Error> {
+        layout(tree, config, |row| {
+            writeln!(out, "{}{}", row.guides, self.line(row.item, config))
+        })?;
+
+        if config.report {
+            writeln!(out, "\n{}", report(tree))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Render a single section of an item, returning [None] if there is nothing to show
+pub(crate) fn section(
+    section: Section,
+    item: &FileTreeItem,
+    config: &FormatConfig,
+) -> Option<String> {
+    match section {
+        Section::Icon => config
+            .icons
+            .icon(&item.file_type, item.ext.as_deref())
+            .map(String::from),
+        Section::Name => Some(label(item)),
+        Section::Error => item.error.map(|e| format!("[{e}]")),
+        Section::Comment => item.description.as_ref().map(|d| format!("# {d}")),
+        Section::Times => {
+            let modified = item.metadata.as_ref()?.modified?;
+            Some(format!("[{}]", relative_time(modified, SystemTime::now())))
+        }
+        Section::Size => Some(format!("[{}]", item.metadata.as_ref()?.size_string())),
+        Section::Permissions => Some(format!(
+            "[{}]",
+            item.metadata.as_ref()?.permissions_string()?
+        )),
+        Section::Owner => Some(format!("[{}]", item.metadata.as_ref()?.owner_string()?)),
+        Section::Contents => item.summary.as_ref().map(|s| format!("[{s}]")),
+    }
+}
+
+/// The numbers of directories and files in a tree, not including the root, e.g.
+/// `2 directories, 3 files`
+pub(crate) fn report(tree: &FileTree) -> String {
+    let (mut directories, mut files) = (0, 0);
+
+    for (_, item, ..) in tree.tree().pre_order().skip(1) {
+        match item.file_type {
+            FileType::Truncated => {}
+            _ if item.is_directory() => directories += 1,
+            _ => files += 1,
+        }
+    }
+
+    counts(directories, files)
+}
+
+/// The name to display for an item. The root of a tree built from a path like `./` has no file
+/// name, so its path is used instead.
+pub(crate) fn display_name(item: &FileTreeItem) -> String {
+    if item.name.is_empty() {
+        item.path.display().to_string()
+    } else {
+        item.name.clone()
+    }
+}
+
+/// The name of an item followed by the target of a symlink, as in `name -> target`
+pub(crate) fn label(item: &FileTreeItem) -> String {
+    match &item.file_type {
+        FileType::Symlink { target, .. } => {
+            format!("{} -> {}", display_name(item), target.display())
+        }
+        _ => display_name(item),
+    }
+}
+
+/// A single laid out line of a tree
+pub(crate) struct Row<'a> {
+    pub item: &'a FileTreeItem,
+    /// The depth of the item, where the root is at depth 0. Only the Markdown format needs it.
+    #[cfg_attr(not(feature = "md"), allow(dead_code))]
+    pub depth: usize,
+    /// The guide characters which precede the item
+    pub guides: String,
+}
+
+/// Lay out a tree line by line, calling `row` for every visible item in display order.
+///
+/// This is shared between every line based format so that they always agree on structure.
+pub(crate) fn layout<E>(
+    tree: &FileTree,
+    config: &FormatConfig,
+    mut row: impl FnMut(Row) -> Result<(), E>,
+) -> Result<(), E> {
+    let chars = TreeChars::new(config.style);
+    // Whether each ancestor of the current item (excluding the root) is the last of its siblings
+    let mut lasts: Vec<bool> = Vec::new();
+
+    for (_, item, depth, last) in tree.tree().pre_order() {
+        if depth == 0 {
+            if config.root {
+                row(Row {
+                    item,
+                    depth,
+                    guides: String::new(),
+                })?;
+            }
+            continue;
+        }
+
+        lasts.truncate(depth - 1);
+        let mut guides = lasts.iter().map(|l| chars.indent(*l)).collect::<String>();
+        guides.push_str(&chars.item(last));
+        lasts.push(last);
+
+        row(Row {
+            item,
+            depth,
+            guides,
+        })?;
+    }
+
+    Ok(())
 }
@@ -1,9 +1,14 @@
 //! `bough-lib` is the crate which powers the [Bough](https://github.com/emmalexandria/bough)
 //! project.
 
+pub mod config;
+pub mod format;
 pub mod output;
 mod tree;
-pub mod format;
 
 pub use tree::file;
+pub use tree::iter;
+pub use tree::metadata;
+pub use tree::summary;
+pub use tree::tree::{Error as TreeError, ErrorKind as TreeErrorKind};
 pub use tree::{ArenaTree, TreeId, TreeItem};
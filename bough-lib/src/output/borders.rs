@@ -15,7 +15,7 @@ pub enum BorderStyle {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
+pub enum Direction {
     Up,
     Down,
     Left,
@@ -233,6 +233,51 @@ impl BorderChars {
     }
 }
 
+/// The characters used to draw the guides of a tree diagram
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeChars {
+    /// Junction leading to an item which has siblings after it
+    pub branch: BorderChar,
+    /// Corner leading to the last item in a directory
+    pub last: BorderChar,
+    /// Line between a junction and the item
+    pub horizontal: BorderChar,
+    /// Line continuing down past the items of a deeper directory
+    pub vertical: BorderChar,
+}
+
+impl TreeChars {
+    /// Create tree guides with the given line style
+    pub fn new(style: BorderStyle) -> Self {
+        Self {
+            branch: BorderChar::new(Connections::t_junction(Direction::Right, style)),
+            last: BorderChar::new(Connections::corner(Direction::Up, Direction::Right, style)),
+            horizontal: BorderChar::new(Connections::horizontal(style)),
+            vertical: BorderChar::new(Connections::vertical(style)),
+        }
+    }
+
+    /// Create light tree guides
+    pub fn light() -> Self {
+        Self::new(BorderStyle::Solid(BorderWeight::Light))
+    }
+
+    /// The guide directly preceding an item, e.g. `├── `
+    pub fn item(&self, last: bool) -> String {
+        let junction = if last { &self.last } else { &self.branch };
+        format!("{junction}{0}{0} ", self.horizontal)
+    }
+
+    /// The indentation added for the items of a directory, e.g. `│   `
+    pub fn indent(&self, last: bool) -> String {
+        if last {
+            String::from("    ")
+        } else {
+            format!("{}   ", self.vertical)
+        }
+    }
+}
+
 /// Convert connections to the appropriate Unicode character
 fn connections_to_unicode(connections: &Connections) -> char {
     use BorderStyle::*;
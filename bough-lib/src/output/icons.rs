@@ -1,8 +1,9 @@
 use std::{fmt::Display, str::FromStr};
 
+use crate::file::FileType;
+
 #[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
-#[cfg(feature="clap")]
-#[derive(ValueEnum)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 pub enum IconType {
     #[default]
     None,
@@ -11,6 +12,80 @@ pub enum IconType {
     Emoji,
 }
 
+impl IconType {
+    /// Get the icon for an item of the given type and extension, or [None] if this style has no
+    /// icons.
+    pub fn icon(&self, file_type: &FileType, ext: Option<&str>) -> Option<&'static str> {
+        if *file_type == FileType::Truncated {
+            return None;
+        }
+
+        match self {
+            Self::None => None,
+            Self::Nerd => Some(nerd_icon(file_type, ext)),
+            Self::Unicode => Some(match file_type {
+                FileType::Directory | FileType::MountPoint => "🗀",
+                FileType::Symlink { .. } => "⮳",
+                FileType::BlockDevice | FileType::CharDevice => "🖴",
+                _ => "🗎",
+            }),
+            Self::Emoji => Some(emoji_icon(file_type, ext)),
+        }
+    }
+}
+
+fn nerd_icon(file_type: &FileType, ext: Option<&str>) -> &'static str {
+    match file_type {
+        FileType::Directory => return "\u{f07b}",
+        FileType::MountPoint => return "\u{f0a0}",
+        FileType::Symlink { .. } => return "\u{f481}",
+        FileType::Executable => return "\u{f489}",
+        FileType::Fifo => return "\u{f0ec}",
+        FileType::Socket => return "\u{f1e6}",
+        FileType::BlockDevice => return "\u{f0c7}",
+        FileType::CharDevice => return "\u{f11c}",
+        _ => {}
+    }
+
+    match ext {
+        Some("rs") => "\u{e7a8}",
+        Some("md") => "\u{e73e}",
+        Some("toml") => "\u{e6b2}",
+        Some("json") => "\u{e60b}",
+        Some("js") => "\u{e74e}",
+        Some("ts") => "\u{e628}",
+        Some("py") => "\u{e73c}",
+        Some("html") => "\u{e736}",
+        Some("css") => "\u{e749}",
+        Some("lua") => "\u{e620}",
+        Some("sh") => "\u{f489}",
+        _ => "\u{f15b}",
+    }
+}
+
+fn emoji_icon(file_type: &FileType, ext: Option<&str>) -> &'static str {
+    match file_type {
+        FileType::Directory => return "📁",
+        FileType::MountPoint => return "💽",
+        FileType::Symlink { .. } => return "🔗",
+        FileType::Executable => return "⚡",
+        FileType::Fifo => return "🚰",
+        FileType::Socket => return "🔌",
+        FileType::BlockDevice => return "💾",
+        FileType::CharDevice => return "⌨️",
+        _ => {}
+    }
+
+    match ext {
+        Some("rs") => "🦀",
+        Some("md" | "txt") => "📝",
+        Some("toml" | "json" | "yaml" | "yml") => "⚙️",
+        Some("png" | "jpg" | "jpeg" | "gif" | "svg") => "🖼️",
+        Some("lock") => "🔒",
+        _ => "📄",
+    }
+}
+
 impl Display for IconType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
@@ -2,8 +2,23 @@ use std::ffi::OsString;
 
 /// Implements the [FileTree](file::FileTree) type and associated types
 pub mod file;
+/// Implements include and exclude globs for a [FileTree](file::FileTree)
+pub(crate) mod filter;
+#[cfg(feature = "ignore")]
+pub(crate) mod ignores;
+/// Implements traversal iterators for [ArenaTree].
+pub mod iter;
+/// Implements optional metadata for the items of a [FileTree](file::FileTree)
+pub mod metadata;
+/// Implements ordering for the children of a [FileTree](file::FileTree)
+pub mod sort;
+/// Implements summaries of the contents of directories in a [FileTree](file::FileTree)
+pub mod summary;
 /// Implements the [ArenaTree] type and associated types.
+#[allow(clippy::module_inception)]
 pub mod tree;
+/// Implements walking the file system for a [FileTree](file::FileTree)
+pub(crate) mod walk;
 
 pub use tree::{ArenaTree, TreeId, TreeItem};
 
@@ -2,36 +2,130 @@
 //!
 //! Internally, [FileTree] uses the [ArenaTree] generic.
 
-use std::fs::read_dir;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs::Metadata;
 use std::io;
 use std::path::{Path, PathBuf};
 
+pub use crate::tree::metadata::FileMetadata;
+use crate::tree::metadata::Owners;
+pub use crate::tree::sort::{SortKey, SortOrder};
+pub use crate::tree::summary::Summary;
 use crate::tree::tree;
-use crate::{tree::os_str_to_string, ArenaTree, TreeItem};
+use crate::tree::walk::{Node, Walker};
+use crate::{ArenaTree, TreeItem, tree::os_str_to_string};
 
 /// The type of the file
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub enum FileType {
     /// Directory
     Directory,
+    /// A directory on a different file system to its parent
+    MountPoint,
     /// File
     File,
+    /// A file with any of its execute permission bits set
+    Executable,
+    /// A named pipe
+    Fifo,
+    /// A Unix domain socket
+    Socket,
+    /// A block device, such as a disk
+    BlockDevice,
+    /// A character device, such as a terminal
+    CharDevice,
+    /// A symbolic link. If links are followed and the target is a directory, the link has the
+    /// contents of the target as children.
+    Symlink {
+        /// The target of the link, as it was written when the link was created
+        target: PathBuf,
+        /// The target of the link does not exist
+        broken: bool,
+    },
+    /// A marker standing in for the contents of a directory which was not walked
+    Truncated,
 }
 
-impl TryFrom<&Path> for FileType {
-    type Error = io::Error;
+impl FileType {
+    /// Classify a file from metadata which was read without following symlinks. The metadata of
+    /// the parent directory is used to detect mount points.
+    pub(crate) fn from_metadata(
+        path: &Path,
+        metadata: &Metadata,
+        parent: Option<&Metadata>,
+    ) -> io::Result<Self> {
+        if metadata.is_symlink() {
+            return Ok(Self::Symlink {
+                target: std::fs::read_link(path)?,
+                broken: !path.exists(),
+            });
+        }
 
-    fn try_from(path: &Path) -> Result<Self, Self::Error> {
-        let metadata = path.metadata()?;
+        #[cfg(unix)]
+        if let Some(special) = Self::unix_special(metadata, parent) {
+            return Ok(special);
+        }
+        #[cfg(not(unix))]
+        let _ = parent;
 
         match metadata.is_dir() {
             true => Ok(Self::Directory),
             false => Ok(Self::File),
         }
     }
+
+    #[cfg(unix)]
+    fn unix_special(metadata: &Metadata, parent: Option<&Metadata>) -> Option<Self> {
+        use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+        let file_type = metadata.file_type();
+
+        if file_type.is_dir() {
+            return parent
+                .is_some_and(|p| p.dev() != metadata.dev())
+                .then_some(Self::MountPoint);
+        }
+
+        if file_type.is_fifo() {
+            Some(Self::Fifo)
+        } else if file_type.is_socket() {
+            Some(Self::Socket)
+        } else if file_type.is_block_device() {
+            Some(Self::BlockDevice)
+        } else if file_type.is_char_device() {
+            Some(Self::CharDevice)
+        } else if metadata.mode() & 0o111 != 0 {
+            Some(Self::Executable)
+        } else {
+            None
+        }
+    }
+
+    /// Returns if this is a [FileType::Directory] or [FileType::MountPoint]
+    #[inline]
+    pub fn is_dir(&self) -> bool {
+        matches!(self, Self::Directory | Self::MountPoint)
+    }
+
+    /// Returns if this is a [FileType::Symlink]
+    #[inline]
+    pub fn is_symlink(&self) -> bool {
+        matches!(self, Self::Symlink { .. })
+    }
 }
 
-type Id = usize;
+impl TryFrom<&Path> for FileType {
+    type Error = io::Error;
+
+    fn try_from(path: &Path) -> Result<Self, Self::Error> {
+        let parent = path.parent().and_then(|p| p.metadata().ok());
+        Self::from_metadata(path, &path.symlink_metadata()?, parent.as_ref())
+    }
+}
+
+/// The ID type used by [FileTree]
+pub type Id = usize;
 
 /// An implementation of [TreeItem] for file trees.
 #[derive(Clone, PartialEq, Eq)]
@@ -47,6 +141,15 @@ pub struct FileTreeItem {
     pub ext: Option<String>,
     /// The [FileType] of the item
     pub file_type: FileType,
+    /// A description of the item, rendered alongside its name
+    pub description: Option<String>,
+    /// Metadata of the item, if it was collected
+    pub metadata: Option<FileMetadata>,
+    /// The error which occurred while reading the item, if the tree was built in tolerant mode.
+    /// For directories this is an error reading their contents.
+    pub error: Option<io::ErrorKind>,
+    /// A summary of the contents of a directory, if [FileTree::summarize] was called
+    pub summary: Option<Summary>,
 }
 
 impl FileTreeItem {
@@ -64,42 +167,64 @@ impl FileTreeItem {
             name,
             ext: path.extension().map(os_str_to_string),
             file_type,
+            description: None,
+            metadata: None,
+            error: None,
+            summary: None,
         };
 
         Ok(ret)
     }
+
+    /// Returns if the item is a directory, or a followed symlink to one
+    pub fn is_directory(&self) -> bool {
+        self.file_type.is_dir() || !self.children.is_empty()
+    }
+
+    /// The size of the item in bytes, read from the file system if metadata was not collected
+    fn size(&self) -> u64 {
+        match &self.metadata {
+            Some(m) => m.size,
+            None => self.path.symlink_metadata().map(|m| m.len()).unwrap_or(0),
+        }
+    }
+
+    /// Create a [FileType::Truncated] marker for the directory at the given path
+    pub fn truncated<P: AsRef<Path>>(path: P, parent: Id) -> Self {
+        Self {
+            parent: Some(parent),
+            children: Vec::new(),
+
+            path: path.as_ref().into(),
+            name: String::from("…"),
+            ext: None,
+            file_type: FileType::Truncated,
+            description: None,
+            metadata: None,
+            error: None,
+            summary: None,
+        }
+    }
 }
 
 impl std::fmt::Debug for FileTreeItem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let end = match self.file_type {
-            FileType::Directory => "(dir)",
-            FileType::File => "",
+        let end = match &self.file_type {
+            FileType::Directory => String::from("(dir)"),
+            FileType::MountPoint => String::from("(mount point)"),
+            FileType::File => String::new(),
+            FileType::Executable => String::from("(executable)"),
+            FileType::Fifo => String::from("(fifo)"),
+            FileType::Socket => String::from("(socket)"),
+            FileType::BlockDevice => String::from("(block device)"),
+            FileType::CharDevice => String::from("(char device)"),
+            FileType::Symlink { target, .. } => format!("-> {}", target.display()),
+            FileType::Truncated => String::from("(truncated)"),
         };
         write!(f, "{} ({:?}) {}", self.name, self.path, end)
     }
 }
 
-impl FileTreeItem {
-    /// Internal crate function for recursively printing a debug view of a tree item
-    pub(crate) fn debug_recursive(
-        &self,
-        tree: &ArenaTree<Self, usize>,
-        f: &mut std::fmt::Formatter<'_>,
-        depth: usize,
-    ) -> std::fmt::Result {
-        writeln!(f, "{}{:?}", " ".repeat(depth * 2), self)?;
-        for child in &self.children {
-            if let Some(c) = tree.get_node(*child) {
-                let depth = depth + 1;
-                c.debug_recursive(tree, f, depth)?;
-            }
-        }
-
-        Ok(())
-    }
-}
-
 impl TreeItem<Id> for FileTreeItem {
     fn children(&self) -> &Vec<Id> {
         &self.children
@@ -116,12 +241,107 @@ impl TreeItem<Id> for FileTreeItem {
     fn add_child(&mut self, child: Id) {
         self.children.push(child)
     }
+
+    fn remove_child(&mut self, child: Id) {
+        self.children.retain(|c| *c != child)
+    }
+}
+
+/// An error which occurred while walking, recorded by a [FileTree] built in tolerant mode
+#[derive(Debug)]
+pub struct WalkError {
+    /// The path of the entry which could not be read
+    pub path: PathBuf,
+    pub error: io::Error,
+}
+
+impl std::fmt::Display for WalkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.error)
+    }
+}
+
+impl std::error::Error for WalkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Options controlling how a [FileTree] is built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTreeOptions {
+    /// The number of directory levels to walk, where a depth of 1 only includes the direct
+    /// children of the root. [None] walks the whole tree.
+    pub max_depth: Option<usize>,
+    /// Include hidden entries (those whose names begin with `.`)
+    pub hidden: bool,
+    /// Insert a [FileType::Truncated] marker into non-empty directories which were not walked
+    /// because of `max_depth`
+    pub show_truncated: bool,
+    /// Leave out entries matched by `.gitignore`, `.ignore` and `.boughignore` files, along with
+    /// `.git/info/exclude` and the user's global git excludes. Requires the `ignore` feature.
+    pub ignore: bool,
+    /// The order of the children of each directory
+    pub sort: SortOrder,
+    /// Collect [FileMetadata] for every item. This is off by default, as resolving owners and
+    /// times makes walking slower.
+    pub metadata: bool,
+    /// Walk into symlinks which point to directories. Links which would lead back into one of
+    /// their own ancestors are not followed.
+    pub follow_links: bool,
+    /// Keep walking when an entry cannot be read, recording the error on the affected item and
+    /// in [FileTree::errors] rather than failing the whole build
+    pub tolerant: bool,
+    /// Only include files matching any of these globs. Directories are always walked, so that
+    /// matching files inside of them are found.
+    pub include: Vec<String>,
+    /// Leave out files and directories matching any of these globs. Globs containing a `/` are
+    /// matched against the path relative to the root, and a trailing `/` only matches directories.
+    pub exclude: Vec<String>,
+    /// Leave out directories which are empty after filtering
+    pub prune: bool,
+}
+
+impl Default for FileTreeOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            hidden: false,
+            show_truncated: false,
+            ignore: true,
+            sort: SortOrder::default(),
+            metadata: false,
+            follow_links: false,
+            tolerant: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            prune: false,
+        }
+    }
+}
+
+impl FileTreeOptions {
+    /// Returns if an entry with the given name should be included in the tree
+    pub(crate) fn includes(&self, name: &OsStr) -> bool {
+        self.hidden || !name.as_encoded_bytes().starts_with(b".")
+    }
+
+    /// Returns if the directories at the given depth should be walked
+    pub(crate) fn descends(&self, depth: usize) -> bool {
+        self.max_depth.is_none_or(|max| depth < max)
+    }
 }
 
 /// An implementation of a file tree using [ArenaTree]
 pub struct FileTree {
     tree: ArenaTree<FileTreeItem, Id>,
+    /// Index of the IDs of every item by path. [FileType::Truncated] markers are not indexed, as
+    /// they share the path of their directory.
+    paths: HashMap<PathBuf, Id>,
     root_path: PathBuf,
+    options: FileTreeOptions,
+    owners: Owners,
+    errors: Vec<WalkError>,
 }
 
 impl FileTree {
@@ -142,14 +362,133 @@ impl FileTree {
 
         Ok(Self {
             tree,
+            paths: HashMap::new(),
             root_path: path.into(),
+            options: FileTreeOptions::default(),
+            owners: Owners::default(),
+            errors: Vec::new(),
         })
     }
 
+    /// Set all of the options used when building the tree
+    #[must_use = "moves the value of self and returns the modified value"]
+    pub fn options(mut self, options: FileTreeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Limit the number of directory levels which are walked, where a depth of 1 only includes
+    /// the direct children of the root. [None] walks the whole tree.
+    #[must_use = "moves the value of self and returns the modified value"]
+    pub fn max_depth(mut self, depth: Option<usize>) -> Self {
+        self.options.max_depth = depth;
+        self
+    }
+
+    /// Include hidden entries (those whose names begin with `.`)
+    #[must_use = "moves the value of self and returns the modified value"]
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.options.hidden = hidden;
+        self
+    }
+
+    /// Respect ignore files while walking the tree
+    #[must_use = "moves the value of self and returns the modified value"]
+    pub fn ignore(mut self, ignore: bool) -> Self {
+        self.options.ignore = ignore;
+        self
+    }
+
+    /// Set the order of the children of each directory
+    #[must_use = "moves the value of self and returns the modified value"]
+    pub fn sort(mut self, order: SortOrder) -> Self {
+        self.options.sort = order;
+        self
+    }
+
+    /// Collect [FileMetadata] for every item
+    #[must_use = "moves the value of self and returns the modified value"]
+    pub fn metadata(mut self, metadata: bool) -> Self {
+        self.options.metadata = metadata;
+        self
+    }
+
+    /// Walk into symlinks which point to directories
+    #[must_use = "moves the value of self and returns the modified value"]
+    pub fn follow_links(mut self, follow: bool) -> Self {
+        self.options.follow_links = follow;
+        self
+    }
+
+    /// Keep walking when an entry cannot be read
+    #[must_use = "moves the value of self and returns the modified value"]
+    pub fn tolerant(mut self, tolerant: bool) -> Self {
+        self.options.tolerant = tolerant;
+        self
+    }
+
+    /// Only include files matching any of the given globs
+    #[must_use = "moves the value of self and returns the modified value"]
+    pub fn include<S: Into<String>>(mut self, globs: impl IntoIterator<Item = S>) -> Self {
+        self.options.include = globs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Leave out files and directories matching any of the given globs
+    #[must_use = "moves the value of self and returns the modified value"]
+    pub fn exclude<S: Into<String>>(mut self, globs: impl IntoIterator<Item = S>) -> Self {
+        self.options.exclude = globs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Leave out directories which are empty after filtering
+    #[must_use = "moves the value of self and returns the modified value"]
+    pub fn prune(mut self, prune: bool) -> Self {
+        self.options.prune = prune;
+        self
+    }
+
+    /// Mark directories which were not walked because of the max depth
+    #[must_use = "moves the value of self and returns the modified value"]
+    pub fn show_truncated(mut self, show: bool) -> Self {
+        self.options.show_truncated = show;
+        self
+    }
+
+    /// Get the underlying [ArenaTree]
+    #[inline]
+    pub fn tree(&self) -> &ArenaTree<FileTreeItem, Id> {
+        &self.tree
+    }
+
+    /// Get the errors which were recorded while building the tree in tolerant mode
+    #[inline]
+    pub fn errors(&self) -> &[WalkError] {
+        &self.errors
+    }
+
+    /// Get the path the tree was built from
+    #[inline]
+    pub fn root_path(&self) -> &Path {
+        &self.root_path
+    }
+
     /// Build the file tree
     #[must_use = "moves the value of self and returns the modified value"]
-    pub fn build(mut self) -> io::Result<Self> {
-        let root = FileTreeItem {
+    pub fn build(self) -> io::Result<Self> {
+        self.build_with(|walker| walker)
+    }
+
+    /// Build the file tree, walking directories in parallel on the rayon thread pool. The result
+    /// is identical to that of [FileTree::build].
+    #[cfg(feature = "parallel")]
+    #[must_use = "moves the value of self and returns the modified value"]
+    pub fn build_parallel(self) -> io::Result<Self> {
+        self.build_with(|walker| walker.parallel())
+    }
+
+    fn build_with(mut self, setup: impl FnOnce(Walker) -> Walker) -> io::Result<Self> {
+        let mut root = FileTreeItem {
             parent: None,
             children: Vec::new(),
             file_type: FileType::Directory,
@@ -160,51 +499,173 @@ impl FileTree {
                 .unwrap_or_default(),
             ext: self.root_path.extension().map(os_str_to_string),
             path: self.root_path.clone(),
+            description: None,
+            metadata: None,
+            error: None,
+            summary: None,
         };
 
+        if self.options.metadata {
+            self.owners = Owners::load();
+            root.metadata = std::fs::metadata(&self.root_path)
+                .ok()
+                .map(|m| FileMetadata::new(&m, &self.owners));
+        }
+
+        let walker = Walker::new(&self.root_path, &self.options, &self.owners)?;
+        let contents = setup(walker).walk(&self.root_path, 1)?;
+        root.error = contents.error;
+
         self.tree = self.tree.root(root);
+        self.paths.clear();
+        self.paths.insert(self.root_path.clone(), self.tree.root);
+        self.errors = contents.errors;
 
-        self.build_from_directory(self.root_path.clone(), self.tree.root)?;
+        self.insert_walked(contents.nodes, self.tree.root)?;
 
         Ok(self)
     }
 
-    fn build_from_directory<P: AsRef<Path>>(&mut self, path: P, parent: Id) -> io::Result<()> {
-        let path = path.as_ref();
-        let entries = read_dir(path)?;
+    /// Compute a [Summary] for every directory in the tree. File sizes are taken from collected
+    /// [FileMetadata], or read from the file system if metadata was not collected.
+    pub fn summarize(&mut self) {
+        let ids = self
+            .tree
+            .post_order()
+            .map(|(id, ..)| id)
+            .collect::<Vec<_>>();
+
+        // Children are visited first, so their summaries are always ready
+        for id in ids {
+            let Some(item) = self.tree.get_node(id) else {
+                continue;
+            };
+            if !item.is_directory() {
+                continue;
+            }
 
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
-            let metadata = entry.metadata()?;
+            let mut summary = Summary::default();
+            for child in item
+                .children()
+                .iter()
+                .filter_map(|c| self.tree.get_node(*c))
+            {
+                match &child.summary {
+                    _ if child.file_type == FileType::Truncated => {}
+                    Some(s) if child.is_directory() => summary.add_directory(s),
+                    _ => summary.add_file(child.size(), child.ext.as_deref()),
+                }
+            }
 
-            let name = entry.file_name();
-            let ext = path.extension();
+            if let Some(item) = self.tree.get_node_mut(id) {
+                item.summary = Some(summary);
+            }
+        }
+    }
 
-            let file_type = match metadata.is_dir() {
-                true => FileType::Directory,
-                false => FileType::File,
-            };
+    /// Get the summary of the whole tree, if [FileTree::summarize] was called
+    #[inline]
+    pub fn summary(&self) -> Option<&Summary> {
+        self.tree.get_node(self.tree.root)?.summary.as_ref()
+    }
+
+    /// Get an item by its full path
+    #[inline]
+    pub fn get_by_path<P: AsRef<Path>>(&self, path: P) -> Option<&FileTreeItem> {
+        self.tree.get_node(self.id_of(path)?)
+    }
+
+    /// Get an item by its path relative to the root of the tree
+    #[inline]
+    pub fn get_by_relative_path<P: AsRef<Path>>(&self, path: P) -> Option<&FileTreeItem> {
+        self.get_by_path(self.root_path.join(path))
+    }
+
+    /// Get the ID of the item with the given full path
+    #[inline]
+    pub fn id_of<P: AsRef<Path>>(&self, path: P) -> Option<Id> {
+        self.paths.get(path.as_ref()).copied()
+    }
+
+    /// Get a mutable reference to an item by ID.
+    ///
+    /// The path of the item must not be changed through this reference, as the path index would
+    /// no longer match it.
+    #[inline]
+    pub fn get_mut(&mut self, id: Id) -> Option<&mut FileTreeItem> {
+        self.tree.get_node_mut(id)
+    }
 
-            let node = FileTreeItem {
+    /// Insert an item into the tree under its parent, indexing it by path
+    pub fn insert_node(&mut self, node: FileTreeItem) -> Result<Id, tree::Error> {
+        let path = (node.file_type != FileType::Truncated).then(|| node.path.clone());
+        let id = self.tree.insert_node(node)?;
+
+        if let Some(path) = path {
+            self.paths.insert(path, id);
+        }
+
+        Ok(id)
+    }
+
+    /// Remove an item and all of its descendants from the tree, returning the removed items
+    pub fn remove_subtree(&mut self, id: Id) -> Result<Vec<FileTreeItem>, tree::Error> {
+        // Removed items are returned in the same order as a pre-order traversal
+        let ids = self
+            .tree
+            .pre_order_from(id)
+            .map(|(id, ..)| id)
+            .collect::<Vec<_>>();
+        let removed = self.tree.remove_subtree(id)?;
+
+        for (item, id) in removed.iter().zip(ids) {
+            if self.paths.get(&item.path) == Some(&id) {
+                self.paths.remove(&item.path);
+            }
+        }
+
+        Ok(removed)
+    }
+
+    fn insert(&mut self, node: FileTreeItem) -> io::Result<Id> {
+        self.insert_node(node).map_err(|e| match e.kind {
+            tree::ErrorKind::NeedsParent => {
+                io::Error::new(io::ErrorKind::NotFound, "Parent not found")
+            }
+            _ => io::Error::other("Unknown error"),
+        })
+    }
+
+    /// Insert walked entries into the tree in pre-order, so that IDs are assigned in the same
+    /// order however the entries were walked
+    fn insert_walked(&mut self, nodes: Vec<Node>, parent: Id) -> io::Result<()> {
+        for node in nodes {
+            self.errors.extend(node.errors);
+
+            let item = FileTreeItem {
                 parent: Some(parent),
-                path: path.clone(),
                 children: Vec::new(),
-                name: os_str_to_string(name),
-                ext: ext.map(os_str_to_string),
-                file_type,
+                ext: node.path.extension().map(os_str_to_string),
+                path: node.path,
+                name: node.name,
+                file_type: node.file_type,
+                description: None,
+                metadata: node.metadata,
+                error: node.error,
+                summary: None,
             };
-
-            let id = self.tree.insert_node(node).map_err(|e| match e.kind {
-                tree::ErrorKind::NeedsParent => {
-                    io::Error::new(io::ErrorKind::NotFound, "Parent not found")
-                }
-                _ => io::Error::new(io::ErrorKind::Other, "Unknown error"),
-            })?;
-
-            if metadata.is_dir() {
-                self.build_from_directory(path, id)?;
+            let id = self.insert(item)?;
+
+            if node.truncated {
+                let path = self
+                    .tree
+                    .get_node(id)
+                    .map(|i| i.path.clone())
+                    .unwrap_or_default();
+                self.insert(FileTreeItem::truncated(path, id))?;
             }
+
+            self.insert_walked(node.children, id)?;
         }
 
         Ok(())
@@ -217,8 +678,8 @@ impl std::fmt::Debug for FileTree {
         writeln!(f, "Debug tree view")?;
         writeln!(f, "---------------")?;
 
-        if let Some(r) = self.tree.get_node(self.tree.root) {
-            r.debug_recursive(&self.tree, f, 0)?;
+        for (_, item, depth, _) in self.tree.pre_order() {
+            writeln!(f, "{}{:?}", " ".repeat(depth * 2), item)?;
         }
         Ok(())
     }
@@ -0,0 +1,147 @@
+//! Include and exclude globs for a [FileTree](super::file::FileTree).
+//!
+//! Patterns without a `/` are matched against the name of each entry, while patterns containing a
+//! `/` are matched against its path relative to the root of the tree. A trailing `/` restricts a
+//! pattern to directories, so `node_modules/` excludes the directory but not a file of that name.
+//! As in `.gitignore` files, `*` does not match a `/`, so `src/*.rs` only matches files directly
+//! inside of `src`, while `src/**/*.rs` matches them at any depth.
+
+use std::io;
+use std::path::Path;
+
+use globset::{GlobBuilder, GlobMatcher};
+
+/// A single compiled pattern
+#[derive(Debug, Clone)]
+pub(crate) struct Pattern {
+    matcher: GlobMatcher,
+    /// Only match directories
+    dir_only: bool,
+    /// Match the relative path rather than the name
+    full_path: bool,
+}
+
+impl Pattern {
+    pub fn new(pattern: &str) -> io::Result<Self> {
+        let dir_only = pattern.ends_with('/');
+        let pattern = pattern.trim_end_matches('/');
+        let full_path = pattern.contains('/');
+        let pattern = pattern.trim_start_matches('/');
+
+        let matcher = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?
+            .compile_matcher();
+
+        Ok(Self {
+            matcher,
+            dir_only,
+            full_path,
+        })
+    }
+
+    pub fn matches(&self, relative: &Path, name: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+
+        if self.full_path {
+            self.matcher.is_match(relative)
+        } else {
+            self.matcher.is_match(name)
+        }
+    }
+}
+
+/// Compiled include and exclude globs
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Filters {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl Filters {
+    /// Compile the given globs, returning an [io::ErrorKind::InvalidInput] error if any of them are
+    /// invalid
+    pub fn new(include: &[String], exclude: &[String]) -> io::Result<Self> {
+        Ok(Self {
+            include: include
+                .iter()
+                .map(|p| Pattern::new(p))
+                .collect::<io::Result<_>>()?,
+            exclude: exclude
+                .iter()
+                .map(|p| Pattern::new(p))
+                .collect::<io::Result<_>>()?,
+        })
+    }
+
+    /// Returns if an entry should be kept. Excluded entries are always left out, while include
+    /// globs only apply to files, so that the directories containing them are still walked.
+    pub fn keeps(&self, relative: &Path, name: &str, is_dir: bool) -> bool {
+        if self
+            .exclude
+            .iter()
+            .any(|p| p.matches(relative, name, is_dir))
+        {
+            return false;
+        }
+
+        is_dir
+            || self.include.is_empty()
+            || self
+                .include
+                .iter()
+                .any(|p| p.matches(relative, name, is_dir))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::Filters;
+
+    fn filters(include: &[&str], exclude: &[&str]) -> Filters {
+        let strings = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        Filters::new(&strings(include), &strings(exclude)).unwrap()
+    }
+
+    #[test]
+    fn test_filters() {
+        let f = filters(&["*.rs", "Cargo.toml"], &["target/", "src/gen/*.rs"]);
+        let keeps = |path: &str, is_dir| {
+            let name = path.rsplit('/').next().unwrap();
+            f.keeps(Path::new(path), name, is_dir)
+        };
+
+        assert!(keeps("src/main.rs", false));
+        assert!(keeps("Cargo.toml", false));
+        assert!(!keeps("Cargo.lock", false));
+        assert!(keeps("src", true));
+        assert!(!keeps("target", true));
+        assert!(!keeps("src/gen/out.rs", false));
+        assert!(keeps("gen/out.rs", false));
+    }
+
+    #[test]
+    fn test_nested_paths() {
+        let f = filters(&[], &["src/*.rs"]);
+        let keeps = |path: &str| {
+            let name = path.rsplit('/').next().unwrap();
+            f.keeps(Path::new(path), name, false)
+        };
+
+        assert!(!keeps("src/lib.rs"));
+        assert!(keeps("src/tree/file.rs"));
+
+        let f = filters(&[], &["src/**/*.rs"]);
+        assert!(!f.keeps(Path::new("src/tree/file.rs"), "file.rs", false));
+    }
+
+    #[test]
+    fn test_invalid_glob() {
+        assert!(Filters::new(&[String::from("a[")], &[]).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+//! Support for ignore files while walking a [FileTree](super::file::FileTree).
+//!
+//! Ignore files are read from every directory as it is walked, and matched from the innermost
+//! directory outwards. Within a directory, later files in [IGNORE_FILES] take precedence over
+//! earlier ones. `.gitignore` files, `.git/info/exclude` and the user's `core.excludesFile` are
+//! only respected inside of a git repository.
+//!
+//! Every path is matched in its canonical form, so that patterns anchored to a parent directory
+//! still apply when the tree is walked through a relative path such as `./`.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ignore::{
+    Match,
+    gitignore::{Gitignore, GitignoreBuilder},
+};
+
+/// The names of the ignore files read from each directory, from lowest to highest precedence
+pub const IGNORE_FILES: [&str; 3] = [".gitignore", ".ignore", ".boughignore"];
+
+/// A stack of ignore matchers following the directory currently being walked. Matchers are shared,
+/// so that the stack can be cloned cheaply for each branch of a parallel walk.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Ignores {
+    /// Matchers which apply to the whole tree, from highest to lowest precedence
+    global: Vec<Arc<Gitignore>>,
+    /// Matchers for each directory from the outermost to the innermost
+    stack: Vec<Arc<Gitignore>>,
+    /// Whether the tree is inside of a git repository
+    git: bool,
+    /// The root of the tree as it was given, which walked paths start with
+    root: PathBuf,
+    /// The canonical form of `root`
+    canonical: PathBuf,
+}
+
+impl Ignores {
+    /// Create the ignore state for a tree rooted at the given path. This reads the ignore files
+    /// of any parent directories inside of the same git repository.
+    pub fn new(given: &Path) -> Self {
+        let root = given.canonicalize().unwrap_or_else(|_| given.to_path_buf());
+        let git_root = root.ancestors().find(|p| p.join(".git").exists());
+
+        let mut ret = Self {
+            git: git_root.is_some(),
+            root: given.to_path_buf(),
+            canonical: root.clone(),
+            ..Default::default()
+        };
+
+        if let Some(git_root) = git_root {
+            let exclude = git_root.join(".git/info/exclude");
+            ret.global.push(Arc::new(matcher(git_root, [exclude])));
+            ret.global.push(Arc::new(Gitignore::global().0));
+
+            let parents = root
+                .ancestors()
+                .skip(1)
+                .take_while(|p| p.starts_with(git_root))
+                .collect::<Vec<_>>();
+            for parent in parents.into_iter().rev() {
+                ret.push(parent);
+            }
+        }
+
+        ret
+    }
+
+    /// Read the ignore files of a directory that is about to be walked
+    pub fn push(&mut self, dir: &Path) {
+        let dir = self.canonical(dir);
+        let files = IGNORE_FILES
+            .iter()
+            .filter(|f| self.git || **f != ".gitignore")
+            .map(|f| dir.join(f));
+
+        self.stack.push(Arc::new(matcher(&dir, files)));
+    }
+
+    /// Forget the ignore files of the directory which was last pushed
+    pub fn pop(&mut self) {
+        self.stack.pop();
+    }
+
+    /// Returns if the given path should be left out of the tree
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let path = self.canonical(path);
+        for m in self.stack.iter().rev().chain(self.global.iter()) {
+            match m.matched(&path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+
+        false
+    }
+
+    /// Replace the root of the tree at the start of a walked path with its canonical form
+    fn canonical(&self, path: &Path) -> PathBuf {
+        match path.strip_prefix(&self.root) {
+            Ok(relative) => self.canonical.join(relative),
+            Err(_) => path.to_path_buf(),
+        }
+    }
+}
+
+/// Build a single matcher from the ignore files which exist out of `files`
+fn matcher(dir: &Path, files: impl IntoIterator<Item = PathBuf>) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(dir);
+    for file in files.into_iter().filter(|f| f.is_file()) {
+        // A partially invalid file still has its valid globs added
+        let _ = builder.add(file);
+    }
+
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
@@ -0,0 +1,333 @@
+//! Traversal iterators for [ArenaTree].
+//!
+//! Every iterator yields a [Visit](crate::iter::Visit) for each node, containing the node's ID, a reference to the
+//! node, its depth and whether it is the last of its siblings. Depth is always the absolute depth
+//! of the node within the tree, where the root is at depth 0.
+
+use std::collections::VecDeque;
+
+use crate::{ArenaTree, TreeId, TreeItem};
+
+/// A visited node: `(id, node, depth, is_last_sibling)`
+pub type Visit<'a, T, I> = (I, &'a T, usize, bool);
+
+/// A pending node: `(id, depth, is_last_sibling)`
+type Pending<I> = (I, usize, bool);
+
+impl<T, I> ArenaTree<T, I>
+where
+    I: TreeId + Copy,
+    T: TreeItem<I> + Clone + Eq,
+{
+    /// Iterate over the whole tree depth-first, visiting parents before their children
+    #[inline]
+    pub fn pre_order(&self) -> PreOrder<'_, T, I> {
+        self.pre_order_from(self.root)
+    }
+
+    /// Iterate over a node and its descendants depth-first, visiting parents before their
+    /// children
+    pub fn pre_order_from(&self, id: I) -> PreOrder<'_, T, I> {
+        PreOrder {
+            tree: self,
+            stack: self.start(id).into_iter().collect(),
+        }
+    }
+
+    /// Iterate over the whole tree depth-first, visiting children before their parents
+    #[inline]
+    pub fn post_order(&self) -> PostOrder<'_, T, I> {
+        self.post_order_from(self.root)
+    }
+
+    /// Iterate over a node and its descendants depth-first, visiting children before their
+    /// parents
+    pub fn post_order_from(&self, id: I) -> PostOrder<'_, T, I> {
+        PostOrder {
+            tree: self,
+            stack: self.start(id).map(|p| (p, false)).into_iter().collect(),
+        }
+    }
+
+    /// Iterate over the whole tree level by level
+    #[inline]
+    pub fn breadth_first(&self) -> BreadthFirst<'_, T, I> {
+        self.breadth_first_from(self.root)
+    }
+
+    /// Iterate over a node and its descendants level by level
+    pub fn breadth_first_from(&self, id: I) -> BreadthFirst<'_, T, I> {
+        BreadthFirst {
+            tree: self,
+            queue: self.start(id).into_iter().collect(),
+        }
+    }
+
+    /// Iterate over the descendants of a node depth-first, not including the node itself
+    pub fn descendants(&self, id: I) -> PreOrder<'_, T, I> {
+        let mut iter = self.pre_order_from(id);
+        iter.next();
+        iter
+    }
+
+    /// Iterate over the ancestors of a node, starting with its parent and ending with the root
+    pub fn ancestors(&self, id: I) -> Ancestors<'_, T, I> {
+        let depth = self.node_depth(id).unwrap_or_default();
+        Ancestors {
+            tree: self,
+            next: self
+                .get_node(id)
+                .and_then(|n| n.parent())
+                .map(|p| (p, depth.saturating_sub(1))),
+        }
+    }
+
+    /// Iterate over the siblings of a node, not including the node itself
+    pub fn siblings(&self, id: I) -> Siblings<'_, T, I> {
+        let children = self
+            .get_node(id)
+            .and_then(|n| n.parent())
+            .and_then(|p| self.get_node(p))
+            .map(|p| p.children().as_slice())
+            .unwrap_or_default();
+
+        Siblings {
+            tree: self,
+            children,
+            index: 0,
+            skip: id,
+            depth: self.node_depth(id).unwrap_or_default(),
+        }
+    }
+
+    /// Call `f` on a node and its descendants depth-first, visiting parents before their
+    /// children. `f` receives the node's ID, the node, its depth and whether it is the last of
+    /// its siblings.
+    pub fn pre_order_mut<F>(&mut self, id: I, mut f: F)
+    where
+        F: FnMut(I, &mut T, usize, bool),
+    {
+        let visits = self
+            .pre_order_from(id)
+            .map(|(id, _, depth, last)| (id, depth, last))
+            .collect::<Vec<_>>();
+
+        for (id, depth, last) in visits {
+            if let Some(node) = self.get_node_mut(id) {
+                f(id, node, depth, last);
+            }
+        }
+    }
+
+    /// Call `f` on a node and its descendants depth-first, visiting children before their
+    /// parents. This is useful for aggregating values up the tree.
+    pub fn post_order_mut<F>(&mut self, id: I, mut f: F)
+    where
+        F: FnMut(I, &mut T, usize, bool),
+    {
+        let visits = self
+            .post_order_from(id)
+            .map(|(id, _, depth, last)| (id, depth, last))
+            .collect::<Vec<_>>();
+
+        for (id, depth, last) in visits {
+            if let Some(node) = self.get_node_mut(id) {
+                f(id, node, depth, last);
+            }
+        }
+    }
+
+    /// Returns if a node is the last of its parent's children. The root is always last.
+    pub fn is_last_sibling(&self, id: I) -> bool {
+        self.get_node(id)
+            .and_then(|n| n.parent())
+            .and_then(|p| self.get_node(p))
+            .and_then(|p| p.children().last())
+            .is_none_or(|last| *last == id)
+    }
+
+    fn start(&self, id: I) -> Option<Pending<I>> {
+        self.get_node(id)?;
+        Some((id, self.node_depth(id)?, self.is_last_sibling(id)))
+    }
+}
+
+/// The children of a node as pending visits, in order
+fn pending_children<'n, T, I>(
+    node: &'n T,
+    depth: usize,
+) -> impl DoubleEndedIterator<Item = Pending<I>> + 'n
+where
+    I: Copy + 'n,
+    T: TreeItem<I>,
+{
+    let children = node.children();
+    children
+        .iter()
+        .enumerate()
+        .map(move |(i, c)| (*c, depth + 1, i == children.len() - 1))
+}
+
+/// A depth-first iterator visiting parents before their children
+pub struct PreOrder<'a, T, I>
+where
+    I: TreeId + Copy,
+    T: TreeItem<I> + Clone + Eq,
+{
+    tree: &'a ArenaTree<T, I>,
+    stack: Vec<Pending<I>>,
+}
+
+impl<'a, T, I> Iterator for PreOrder<'a, T, I>
+where
+    I: TreeId + Copy,
+    T: TreeItem<I> + Clone + Eq,
+{
+    type Item = Visit<'a, T, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((id, depth, last)) = self.stack.pop() {
+            let Some(node) = self.tree.get_node(id) else {
+                continue;
+            };
+
+            self.stack.extend(pending_children(node, depth).rev());
+            return Some((id, node, depth, last));
+        }
+
+        None
+    }
+}
+
+/// A depth-first iterator visiting children before their parents
+pub struct PostOrder<'a, T, I>
+where
+    I: TreeId + Copy,
+    T: TreeItem<I> + Clone + Eq,
+{
+    tree: &'a ArenaTree<T, I>,
+    /// Pending nodes, and whether their children have already been pushed
+    stack: Vec<(Pending<I>, bool)>,
+}
+
+impl<'a, T, I> Iterator for PostOrder<'a, T, I>
+where
+    I: TreeId + Copy,
+    T: TreeItem<I> + Clone + Eq,
+{
+    type Item = Visit<'a, T, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(((id, depth, last), expanded)) = self.stack.pop() {
+            let Some(node) = self.tree.get_node(id) else {
+                continue;
+            };
+
+            if expanded || node.children().is_empty() {
+                return Some((id, node, depth, last));
+            }
+
+            self.stack.push(((id, depth, last), true));
+            self.stack
+                .extend(pending_children(node, depth).rev().map(|p| (p, false)));
+        }
+
+        None
+    }
+}
+
+/// An iterator visiting the tree level by level
+pub struct BreadthFirst<'a, T, I>
+where
+    I: TreeId + Copy,
+    T: TreeItem<I> + Clone + Eq,
+{
+    tree: &'a ArenaTree<T, I>,
+    queue: VecDeque<Pending<I>>,
+}
+
+impl<'a, T, I> Iterator for BreadthFirst<'a, T, I>
+where
+    I: TreeId + Copy,
+    T: TreeItem<I> + Clone + Eq,
+{
+    type Item = Visit<'a, T, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((id, depth, last)) = self.queue.pop_front() {
+            let Some(node) = self.tree.get_node(id) else {
+                continue;
+            };
+
+            self.queue.extend(pending_children(node, depth));
+            return Some((id, node, depth, last));
+        }
+
+        None
+    }
+}
+
+/// An iterator over the ancestors of a node, from its parent to the root
+pub struct Ancestors<'a, T, I>
+where
+    I: TreeId + Copy,
+    T: TreeItem<I> + Clone + Eq,
+{
+    tree: &'a ArenaTree<T, I>,
+    next: Option<(I, usize)>,
+}
+
+impl<'a, T, I> Iterator for Ancestors<'a, T, I>
+where
+    I: TreeId + Copy,
+    T: TreeItem<I> + Clone + Eq,
+{
+    type Item = Visit<'a, T, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (id, depth) = self.next.take()?;
+        let node = self.tree.get_node(id)?;
+
+        self.next = node.parent().map(|p| (p, depth.saturating_sub(1)));
+        Some((id, node, depth, self.tree.is_last_sibling(id)))
+    }
+}
+
+/// An iterator over the siblings of a node
+pub struct Siblings<'a, T, I>
+where
+    I: TreeId + Copy,
+    T: TreeItem<I> + Clone + Eq,
+{
+    tree: &'a ArenaTree<T, I>,
+    children: &'a [I],
+    index: usize,
+    skip: I,
+    depth: usize,
+}
+
+impl<'a, T, I> Iterator for Siblings<'a, T, I>
+where
+    I: TreeId + Copy,
+    T: TreeItem<I> + Clone + Eq,
+{
+    type Item = Visit<'a, T, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(id) = self.children.get(self.index).copied() {
+            self.index += 1;
+            if id == self.skip {
+                continue;
+            }
+
+            let Some(node) = self.tree.get_node(id) else {
+                continue;
+            };
+
+            let last = self.index == self.children.len();
+            return Some((id, node, self.depth, last));
+        }
+
+        None
+    }
+}
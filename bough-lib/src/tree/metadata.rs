@@ -0,0 +1,245 @@
+//! Optional file metadata collected while walking a [FileTree](super::file::FileTree), along with
+//! helpers for rendering it.
+
+use std::collections::HashMap;
+use std::fs::Metadata;
+use std::time::{Duration, SystemTime};
+
+/// Metadata of a file, collected when [FileTreeOptions::metadata](super::file::FileTreeOptions)
+/// is enabled. Fields which are unavailable on the current platform are [None].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct FileMetadata {
+    /// Size in bytes
+    pub size: u64,
+    pub created: Option<SystemTime>,
+    pub modified: Option<SystemTime>,
+    pub accessed: Option<SystemTime>,
+    /// Unix file type and permission bits, as in `st_mode`
+    pub mode: Option<u32>,
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+    /// The name of the owning user
+    pub user: Option<String>,
+    /// The name of the owning group
+    pub group: Option<String>,
+    pub inode: Option<u64>,
+    /// The number of hard links
+    pub links: Option<u64>,
+}
+
+impl FileMetadata {
+    /// Collect metadata, resolving user and group names from `owners`
+    pub(crate) fn new(metadata: &Metadata, owners: &Owners) -> Self {
+        let mut ret = Self {
+            size: metadata.len(),
+            created: metadata.created().ok(),
+            modified: metadata.modified().ok(),
+            accessed: metadata.accessed().ok(),
+            ..Default::default()
+        };
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+
+            ret.mode = Some(metadata.mode());
+            ret.uid = Some(metadata.uid());
+            ret.gid = Some(metadata.gid());
+            ret.user = owners.users.get(&metadata.uid()).cloned();
+            ret.group = owners.groups.get(&metadata.gid()).cloned();
+            ret.inode = Some(metadata.ino());
+            ret.links = Some(metadata.nlink());
+        }
+        #[cfg(not(unix))]
+        let _ = owners;
+
+        ret
+    }
+
+    /// The size in a human readable form, e.g. `4.0K`
+    pub fn size_string(&self) -> String {
+        human_size(self.size)
+    }
+
+    /// The permissions in the form printed by `ls -l`, e.g. `drwxr-xr-x`
+    pub fn permissions_string(&self) -> Option<String> {
+        self.mode.map(mode_string)
+    }
+
+    /// The owner as `user:group`, falling back to IDs if names could not be resolved
+    pub fn owner_string(&self) -> Option<String> {
+        let user = self
+            .user
+            .clone()
+            .or_else(|| self.uid.map(|u| u.to_string()))?;
+        let group = self
+            .group
+            .clone()
+            .or_else(|| self.gid.map(|g| g.to_string()))?;
+
+        Some(format!("{user}:{group}"))
+    }
+}
+
+/// User and group names by ID
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Owners {
+    users: HashMap<u32, String>,
+    groups: HashMap<u32, String>,
+}
+
+impl Owners {
+    /// Read the names of local users and groups from `/etc/passwd` and `/etc/group`
+    pub fn load() -> Self {
+        Self {
+            users: read_id_names("/etc/passwd"),
+            groups: read_id_names("/etc/group"),
+        }
+    }
+}
+
+/// Read `name:password:id:...` lines into a map of ID to name
+fn read_id_names(path: &str) -> HashMap<u32, String> {
+    let contents = std::fs::read_to_string(path).unwrap_or_default();
+
+    contents
+        .lines()
+        .filter_map(|l| {
+            let mut fields = l.split(':');
+            let name = fields.next()?;
+            let id = fields.nth(1)?.parse().ok()?;
+            Some((id, name.to_string()))
+        })
+        .collect()
+}
+
+/// Format a size in bytes using binary units, e.g. `512`, `4.0K` or `1.5M`
+pub fn human_size(size: u64) -> String {
+    const UNITS: [&str; 6] = ["K", "M", "G", "T", "P", "E"];
+
+    if size < 1024 {
+        return size.to_string();
+    }
+
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if value < 10.0 {
+        format!("{value:.1}{}", UNITS[unit])
+    } else {
+        format!("{value:.0}{}", UNITS[unit])
+    }
+}
+
+/// Format a time relative to `now`, e.g. `just now`, `5 minutes ago` or `in 2 days`
+pub fn relative_time(time: SystemTime, now: SystemTime) -> String {
+    let (elapsed, future) = match now.duration_since(time) {
+        Ok(d) => (d, false),
+        Err(e) => (e.duration(), true),
+    };
+
+    if elapsed < Duration::from_secs(60) {
+        return String::from("just now");
+    }
+
+    const UNITS: [(u64, &str); 6] = [
+        (60 * 60 * 24 * 365, "year"),
+        (60 * 60 * 24 * 30, "month"),
+        (60 * 60 * 24 * 7, "week"),
+        (60 * 60 * 24, "day"),
+        (60 * 60, "hour"),
+        (60, "minute"),
+    ];
+
+    let secs = elapsed.as_secs();
+    let (size, name) = UNITS
+        .iter()
+        .find(|(size, _)| secs >= *size)
+        .copied()
+        .unwrap_or((60, "minute"));
+    let count = secs / size;
+    let plural = if count == 1 { "" } else { "s" };
+
+    if future {
+        format!("in {count} {name}{plural}")
+    } else {
+        format!("{count} {name}{plural} ago")
+    }
+}
+
+/// Format Unix mode bits in the form printed by `ls -l`, e.g. `-rw-r--r--`
+pub fn mode_string(mode: u32) -> String {
+    let kind = match mode & 0o170000 {
+        0o040000 => 'd',
+        0o120000 => 'l',
+        0o010000 => 'p',
+        0o140000 => 's',
+        0o060000 => 'b',
+        0o020000 => 'c',
+        _ => '-',
+    };
+
+    let mut out = String::with_capacity(10);
+    out.push(kind);
+
+    // (read, write, execute, special bit, special char)
+    let triples = [
+        (0o400, 0o200, 0o100, 0o4000, 's'),
+        (0o040, 0o020, 0o010, 0o2000, 's'),
+        (0o004, 0o002, 0o001, 0o1000, 't'),
+    ];
+    for (r, w, x, special, c) in triples {
+        out.push(if mode & r != 0 { 'r' } else { '-' });
+        out.push(if mode & w != 0 { 'w' } else { '-' });
+        out.push(match (mode & x != 0, mode & special != 0) {
+            (true, true) => c,
+            (false, true) => c.to_ascii_uppercase(),
+            (true, false) => 'x',
+            (false, false) => '-',
+        });
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::{human_size, mode_string, relative_time};
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!(human_size(512), "512");
+        assert_eq!(human_size(4096), "4.0K");
+        assert_eq!(human_size(1536 * 1024), "1.5M");
+        assert_eq!(human_size(20 * 1024 * 1024 * 1024), "20G");
+    }
+
+    #[test]
+    fn test_relative_time() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let ago = |secs| now - Duration::from_secs(secs);
+
+        assert_eq!(relative_time(ago(5), now), "just now");
+        assert_eq!(relative_time(ago(60), now), "1 minute ago");
+        assert_eq!(relative_time(ago(3 * 86400 + 10), now), "3 days ago");
+        assert_eq!(
+            relative_time(now + Duration::from_secs(7200), now),
+            "in 2 hours"
+        );
+    }
+
+    #[test]
+    fn test_mode_string() {
+        assert_eq!(mode_string(0o100644), "-rw-r--r--");
+        assert_eq!(mode_string(0o040755), "drwxr-xr-x");
+        assert_eq!(mode_string(0o104755), "-rwsr-xr-x");
+        assert_eq!(mode_string(0o041777), "drwxrwxrwt");
+        assert_eq!(mode_string(0o120777), "lrwxrwxrwx");
+    }
+}
@@ -0,0 +1,163 @@
+//! Ordering of the children of a [FileTree](super::file::FileTree).
+
+use std::cmp::Ordering;
+use std::fs::Metadata;
+
+/// The key which children are sorted by
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortKey {
+    /// Keep the order the file system returns entries in. This is not consistent between
+    /// platforms or file systems.
+    None,
+    /// Name, compared character by character
+    Name,
+    /// Name, treating runs of digits as numbers so that `v2` comes before `v10`
+    #[default]
+    Natural,
+    /// Extension, then name
+    Extension,
+    /// Size in bytes, then name
+    Size,
+    /// Last modification time, then name
+    Modified,
+}
+
+/// The order of the children of each directory
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SortOrder {
+    pub key: SortKey,
+    /// Place directories before files. This is applied before `reverse`.
+    pub directories_first: bool,
+    /// Ignore case when comparing names and extensions
+    pub case_insensitive: bool,
+    /// Reverse the order given by `key`
+    pub reverse: bool,
+}
+
+/// An entry which is being sorted
+pub(crate) struct SortEntry<'a> {
+    pub name: &'a str,
+    /// The metadata of the entry, if it could be read
+    pub metadata: Option<&'a Metadata>,
+}
+
+impl SortOrder {
+    /// Compare two entries
+    pub(crate) fn compare(&self, a: &SortEntry, b: &SortEntry) -> Ordering {
+        if self.directories_first {
+            let dirs = is_dir(b).cmp(&is_dir(a));
+            if dirs != Ordering::Equal {
+                return dirs;
+            }
+        }
+
+        let ord = match self.key {
+            SortKey::None => Ordering::Equal,
+            SortKey::Name => self.compare_names(a.name, b.name, false),
+            SortKey::Natural => self.compare_names(a.name, b.name, true),
+            SortKey::Extension => self
+                .compare_names(extension(a.name), extension(b.name), false)
+                .then_with(|| self.compare_names(a.name, b.name, true)),
+            SortKey::Size => a
+                .metadata
+                .map(Metadata::len)
+                .cmp(&b.metadata.map(Metadata::len))
+                .then_with(|| self.compare_names(a.name, b.name, true)),
+            SortKey::Modified => a
+                .metadata
+                .and_then(|m| m.modified().ok())
+                .cmp(&b.metadata.and_then(|m| m.modified().ok()))
+                .then_with(|| self.compare_names(a.name, b.name, true)),
+        };
+
+        if self.reverse { ord.reverse() } else { ord }
+    }
+
+    fn compare_names(&self, a: &str, b: &str, natural: bool) -> Ordering {
+        let ord = if self.case_insensitive {
+            let (a, b) = (a.to_lowercase(), b.to_lowercase());
+            if natural {
+                natural_cmp(&a, &b)
+            } else {
+                a.cmp(&b)
+            }
+        } else if natural {
+            natural_cmp(a, b)
+        } else {
+            Ordering::Equal
+        };
+
+        // Fall back to an exact comparison so the order is always total
+        ord.then_with(|| a.cmp(b))
+    }
+}
+
+/// Returns if an entry is a directory, treating entries without metadata as files
+fn is_dir(entry: &SortEntry) -> bool {
+    entry.metadata.is_some_and(Metadata::is_dir)
+}
+
+/// The extension of a file name, treating dotfiles as having no extension
+fn extension(name: &str) -> &str {
+    match name.rfind('.') {
+        Some(0) | None => "",
+        Some(i) => &name[i + 1..],
+    }
+}
+
+/// Compare two strings, treating runs of ASCII digits as numbers
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+
+    loop {
+        let (Some(ca), Some(cb)) = (a.chars().next(), b.chars().next()) else {
+            return a.len().cmp(&b.len());
+        };
+
+        if ca.is_ascii_digit() && cb.is_ascii_digit() {
+            let (na, ra) = split_digits(a);
+            let (nb, rb) = split_digits(b);
+
+            let (ta, tb) = (na.trim_start_matches('0'), nb.trim_start_matches('0'));
+            let ord = ta
+                .len()
+                .cmp(&tb.len())
+                .then_with(|| ta.cmp(tb))
+                // Equal numbers with more leading zeros sort later
+                .then_with(|| na.len().cmp(&nb.len()));
+
+            if ord != Ordering::Equal {
+                return ord;
+            }
+
+            (a, b) = (ra, rb);
+        } else {
+            let ord = ca.cmp(&cb);
+            if ord != Ordering::Equal {
+                return ord;
+            }
+
+            (a, b) = (&a[ca.len_utf8()..], &b[cb.len_utf8()..]);
+        }
+    }
+}
+
+fn split_digits(s: &str) -> (&str, &str) {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s.split_at(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::natural_cmp;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn test_natural_cmp() {
+        assert_eq!(natural_cmp("v2", "v10"), Ordering::Less);
+        assert_eq!(natural_cmp("file10.txt", "file9.txt"), Ordering::Greater);
+        assert_eq!(natural_cmp("a01", "a1"), Ordering::Greater);
+        assert_eq!(natural_cmp("abc", "abc"), Ordering::Equal);
+        assert_eq!(natural_cmp("ab", "abc"), Ordering::Less);
+    }
+}
@@ -0,0 +1,67 @@
+//! Aggregated summaries of the contents of directories in a [FileTree](super::file::FileTree).
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use crate::tree::metadata::human_size;
+
+/// A summary of everything inside of a directory, computed by
+/// [FileTree::summarize](super::file::FileTree::summarize)
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Summary {
+    /// The total size in bytes of all files inside of the directory
+    pub size: u64,
+    /// The number of files inside of the directory, at any depth
+    pub files: usize,
+    /// The number of directories inside of the directory, at any depth
+    pub directories: usize,
+    /// The number of files with each extension
+    pub extensions: BTreeMap<String, usize>,
+}
+
+impl Summary {
+    /// Add the summary of a subdirectory to this one
+    pub(crate) fn add_directory(&mut self, other: &Summary) {
+        self.size += other.size;
+        self.files += other.files;
+        self.directories += other.directories + 1;
+
+        for (ext, count) in &other.extensions {
+            *self.extensions.entry(ext.clone()).or_default() += count;
+        }
+    }
+
+    /// Add a single file to this summary
+    pub(crate) fn add_file(&mut self, size: u64, ext: Option<&str>) {
+        self.size += size;
+        self.files += 1;
+
+        if let Some(ext) = ext {
+            *self.extensions.entry(ext.to_string()).or_default() += 1;
+        }
+    }
+
+    /// The counts in the form printed at the end of `tree`, e.g. `2 directories, 1 file`
+    pub fn counts_string(&self) -> String {
+        counts(self.directories, self.files)
+    }
+}
+
+impl Display for Summary {
+    /// Formats as e.g. `2 directories, 5 files, 4.0K`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, {}", self.counts_string(), human_size(self.size))
+    }
+}
+
+/// Format numbers of directories and files, e.g. `1 directory, 3 files`
+pub(crate) fn counts(directories: usize, files: usize) -> String {
+    let dirs = if directories == 1 {
+        "directory"
+    } else {
+        "directories"
+    };
+    let file = if files == 1 { "file" } else { "files" };
+
+    format!("{directories} {dirs}, {files} {file}")
+}
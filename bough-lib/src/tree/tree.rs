@@ -1,4 +1,4 @@
-use std::{error::Error as StdError, fmt::Display};
+use std::{collections::HashMap, error::Error as StdError, fmt::Display};
 
 /// A trait for valid IDs to be used within the tree. IDs must be convertible to and from usize and
 /// comparable. This is implemented for most numeric types
@@ -35,12 +35,19 @@ pub trait TreeItem<I> {
 
     /// Add a child to the items children.
     fn add_child(&mut self, child: I);
+
+    /// Remove a child from the items children.
+    fn remove_child(&mut self, child: I);
 }
 
 #[derive(Debug)]
 pub enum ErrorKind {
     DoesNotExist,
     NeedsParent,
+    /// The operation cannot be applied to the root of the tree
+    IsRoot,
+    /// The operation would make a node its own ancestor
+    Cycle,
 }
 
 #[derive(Debug)]
@@ -112,6 +119,8 @@ where
     /// Create a new empty tree without a root
     #[inline]
     pub const fn empty(capacity: usize) -> Self {
+        // Allocating is not possible in a const fn, so the capacity is only a hint for now
+        let _ = capacity;
         Self {
             root: I::INVALID,
             free: Vec::new(),
@@ -144,32 +153,80 @@ where
     /// Insert a node into the tree. This node must have a parent ID.
     #[inline]
     pub fn insert_node(&mut self, child: T) -> Result<I, Error> {
-        let id = self.allocate_id();
-        if child.parent().is_none() {
+        let Some(parent) = child.parent() else {
             return Err(Error::new(
                 ErrorKind::NeedsParent,
                 "Inserting a node requires it has a parent",
             ));
-        }
+        };
 
-        let parent = self.get_node_mut(child.parent().unwrap());
-        if parent.is_none() {
+        if self.get_node(parent).is_none() {
             return Err(Error::new(
                 ErrorKind::DoesNotExist,
                 "Could not create node as parent does not exist",
             ));
         }
 
-        parent.unwrap().add_child(id);
+        let id = self.allocate_id();
+        if let Some(p) = self.get_node_mut(parent) {
+            p.add_child(id);
+        }
         self.nodes[id.into()] = Some(child);
 
         Ok(id)
     }
 
-    /// Copy a node from one part of the tree to another
+    /// Remove a node and all of its descendants from the tree, returning the removed node.
+    ///
+    /// The IDs of the node and its descendants are freed and will be reused by later inserts.
+    #[inline]
+    pub fn remove_node(&mut self, id: I) -> Result<T, Error> {
+        let mut removed = self.remove_subtree(id)?;
+        Ok(removed.swap_remove(0))
+    }
+
+    /// Remove a node and all of its descendants from the tree, returning the removed items with
+    /// the node first and its descendants following in depth-first order.
+    ///
+    /// The node is detached from its parent's children and all of the removed IDs are freed.
+    /// The root of the tree cannot be removed.
+    pub fn remove_subtree(&mut self, id: I) -> Result<Vec<T>, Error> {
+        if id == self.root {
+            return Err(Error::new(
+                ErrorKind::IsRoot,
+                "The root of the tree cannot be removed",
+            ));
+        }
+
+        let Some(node) = self.get_node(id) else {
+            return Err(Error::new(
+                ErrorKind::DoesNotExist,
+                "Node does not exist to remove",
+            ));
+        };
+
+        if let Some(parent) = node.parent().and_then(|p| self.get_node_mut(p)) {
+            parent.remove_child(id);
+        }
+
+        let mut removed = Vec::new();
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            if let Some(node) = self.nodes.get_mut(id.into()).and_then(Option::take) {
+                stack.extend(node.children().iter().rev());
+                self.free.push(id);
+                removed.push(node);
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Copy a single node from one part of the tree to another. The copy has no children, see
+    /// [copy_subtree](ArenaTree::copy_subtree) to copy its descendants as well.
     #[inline]
     pub fn copy_node(&mut self, node: I, new_parent: I) -> Result<I, Error> {
-        let node = self.get_node(node).cloned();
+        let node = self.detached_clone(node);
 
         if let Some(mut n) = node {
             n.set_parent(new_parent);
@@ -182,6 +239,131 @@ where
         ))
     }
 
+    /// Copy a node and all of its descendants to a new parent, returning the ID of the copied
+    /// node. Every copied node is given a fresh ID.
+    ///
+    /// A node may be copied into its own subtree, in which case the copy contains the subtree as
+    /// it was before copying.
+    pub fn copy_subtree(&mut self, node: I, new_parent: I) -> Result<I, Error> {
+        if self.get_node(new_parent).is_none() {
+            return Err(Error::new(
+                ErrorKind::DoesNotExist,
+                "Could not copy node as the new parent does not exist",
+            ));
+        }
+
+        let ids = self.subtree_ids(node);
+        if ids.is_empty() {
+            return Err(Error::new(
+                ErrorKind::DoesNotExist,
+                "Source node does not exist to copy",
+            ));
+        }
+
+        // Maps the old IDs of copied nodes to their new IDs. IDs are visited parent first, so the
+        // new parent of every node is known by the time it is copied.
+        let mut copied: HashMap<usize, I> = HashMap::with_capacity(ids.len());
+        for id in ids {
+            let Some(mut n) = self.detached_clone(id) else {
+                continue;
+            };
+
+            let parent = match n.parent().and_then(|p| copied.get(&p.into())) {
+                Some(p) if id != node => *p,
+                _ => new_parent,
+            };
+            n.set_parent(parent);
+
+            copied.insert(id.into(), self.insert_node(n)?);
+        }
+
+        Ok(copied[&node.into()])
+    }
+
+    /// Move a node and its descendants to a new parent, keeping their IDs.
+    ///
+    /// The root cannot be moved, and a node cannot be moved into its own subtree.
+    pub fn move_node(&mut self, node: I, new_parent: I) -> Result<(), Error> {
+        if node == self.root {
+            return Err(Error::new(
+                ErrorKind::IsRoot,
+                "The root of the tree cannot be moved",
+            ));
+        }
+
+        let Some(old_parent) = self.get_node(node).map(|n| n.parent()) else {
+            return Err(Error::new(
+                ErrorKind::DoesNotExist,
+                "Node does not exist to move",
+            ));
+        };
+
+        if self.get_node(new_parent).is_none() {
+            return Err(Error::new(
+                ErrorKind::DoesNotExist,
+                "Could not move node as the new parent does not exist",
+            ));
+        }
+
+        if self.is_ancestor(node, new_parent) {
+            return Err(Error::new(
+                ErrorKind::Cycle,
+                "A node cannot be moved into its own subtree",
+            ));
+        }
+
+        if let Some(p) = old_parent.and_then(|p| self.get_node_mut(p)) {
+            p.remove_child(node);
+        }
+        if let Some(p) = self.get_node_mut(new_parent) {
+            p.add_child(node);
+        }
+        if let Some(n) = self.get_node_mut(node) {
+            n.set_parent(new_parent);
+        }
+
+        Ok(())
+    }
+
+    /// Returns if `ancestor` is `node` or one of its ancestors
+    pub fn is_ancestor(&self, ancestor: I, node: I) -> bool {
+        let mut curr = Some(node);
+
+        while let Some(id) = curr {
+            if id == ancestor {
+                return true;
+            }
+            curr = self.get_node(id).and_then(|n| n.parent());
+        }
+
+        false
+    }
+
+    /// Clone a node without its children
+    fn detached_clone(&self, id: I) -> Option<T> {
+        let mut node = self.get_node(id)?.clone();
+        for child in node.children().clone() {
+            node.remove_child(child);
+        }
+
+        Some(node)
+    }
+
+    /// The IDs of a node and its descendants, parents before their children
+    fn subtree_ids(&self, id: I) -> Vec<I> {
+        let mut ids = Vec::new();
+        let mut stack = vec![id];
+
+        while let Some(id) = stack.pop() {
+            if let Some(node) = self.get_node(id) {
+                stack.extend(node.children().iter().rev());
+                ids.push(id);
+            }
+        }
+
+        ids
+    }
+
     /// Find a node by value
     #[inline]
     pub fn find_node(&self, node: &T) -> Option<I> {
@@ -207,6 +389,12 @@ where
         self.assigned_nodes().len()
     }
 
+    /// Returns if the tree has no nodes
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.iter().all(Option::is_none)
+    }
+
     /// Calculate the depth of a node by following its parents.
     ///
     /// It is recommended to maintain a depth field inside your TreeItem over using
@@ -233,11 +421,7 @@ where
     /// Get a vector of pointers to the nodes which are assigned (not None)
     #[inline]
     pub fn assigned_nodes(&self) -> Vec<&T> {
-        self.nodes
-            .iter()
-            .filter(|n| n.is_some())
-            .map(|n| n.as_ref().unwrap())
-            .collect::<Vec<_>>()
+        self.nodes.iter().flatten().collect::<Vec<_>>()
     }
 }
 
@@ -267,6 +451,10 @@ mod tests {
         fn add_child(&mut self, child: usize) {
             self.children.push(child);
         }
+
+        fn remove_child(&mut self, child: usize) {
+            self.children.retain(|c| *c != child);
+        }
     }
 
     fn create_test_node(parent: usize) -> BasicNode {
@@ -296,7 +484,7 @@ mod tests {
         ];
 
         for child in root_children {
-            tree.insert_node(child);
+            tree.insert_node(child).unwrap();
         }
 
         tree
@@ -304,13 +492,175 @@ mod tests {
 
     #[test]
     fn test_node_depth() {
-        let root = BasicNode {
-            children: Vec::new(),
-            parent: None,
-        };
-
         let tree = create_test_tree();
 
         assert_eq!(tree.node_depth(1), Some(1));
     }
+
+    #[test]
+    fn test_remove_subtree() {
+        let mut tree = create_test_tree();
+        let grandchild = tree.insert_node(create_test_node(1)).unwrap();
+        tree.insert_node(create_test_node(grandchild)).unwrap();
+
+        let removed = tree.remove_subtree(1).unwrap();
+
+        assert_eq!(removed.len(), 3);
+        assert_eq!(removed[1].parent, Some(1));
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.get_node(tree.root).unwrap().children, vec![2]);
+        assert!(tree.get_node(grandchild).is_none());
+    }
+
+    #[test]
+    fn test_removed_ids_are_reused() {
+        let mut tree = create_test_tree();
+
+        tree.remove_node(2).unwrap();
+        let id = tree.insert_node(create_test_node(1)).unwrap();
+
+        assert_eq!(id, 2);
+        assert_eq!(tree.nodes.len(), 3);
+    }
+
+    #[test]
+    fn test_remove_root() {
+        let mut tree = create_test_tree();
+
+        assert!(tree.remove_node(tree.root).is_err());
+        assert!(tree.remove_node(10).is_err());
+    }
+
+    #[test]
+    fn test_copy_subtree() {
+        let mut tree = create_test_tree();
+        let grandchild = tree.insert_node(create_test_node(1)).unwrap();
+
+        let copy = tree.copy_subtree(1, 2).unwrap();
+        let copied = tree.get_node(copy).unwrap();
+
+        assert_eq!(copied.parent, Some(2));
+        assert_eq!(copied.children.len(), 1);
+        assert_ne!(copied.children[0], grandchild);
+        assert_eq!(
+            tree.get_node(copied.children[0]).unwrap().parent,
+            Some(copy)
+        );
+        assert_eq!(tree.len(), 6);
+    }
+
+    #[test]
+    fn test_copy_subtree_into_itself() {
+        let mut tree = create_test_tree();
+        let grandchild = tree.insert_node(create_test_node(1)).unwrap();
+
+        tree.copy_subtree(1, grandchild).unwrap();
+
+        assert_eq!(tree.len(), 6);
+        assert_eq!(tree.node_depth(tree.nodes.len() - 1), Some(4));
+    }
+
+    #[test]
+    fn test_copy_node_has_no_children() {
+        let mut tree = create_test_tree();
+        tree.insert_node(create_test_node(1)).unwrap();
+
+        let copy = tree.copy_node(1, 2).unwrap();
+
+        assert!(tree.get_node(copy).unwrap().children.is_empty());
+    }
+
+    #[test]
+    fn test_move_node() {
+        let mut tree = create_test_tree();
+        let grandchild = tree.insert_node(create_test_node(1)).unwrap();
+
+        tree.move_node(1, 2).unwrap();
+
+        assert_eq!(tree.get_node(tree.root).unwrap().children, vec![2]);
+        assert_eq!(tree.get_node(2).unwrap().children, vec![1]);
+        assert_eq!(tree.node_depth(grandchild), Some(3));
+    }
+
+    #[test]
+    fn test_move_node_rejects_cycles() {
+        let mut tree = create_test_tree();
+        let grandchild = tree.insert_node(create_test_node(1)).unwrap();
+
+        assert!(tree.move_node(1, grandchild).is_err());
+        assert!(tree.move_node(1, 1).is_err());
+        assert!(tree.move_node(tree.root, 1).is_err());
+        assert_eq!(tree.get_node(1).unwrap().parent, Some(tree.root));
+    }
+
+    /// Builds the tree `0 -> [1 -> [3, 4], 2]`
+    fn create_nested_tree() -> ArenaTree<BasicNode, usize> {
+        let mut tree = create_test_tree();
+        tree.insert_node(create_test_node(1)).unwrap();
+        tree.insert_node(create_test_node(1)).unwrap();
+        tree
+    }
+
+    #[test]
+    fn test_pre_order() {
+        let tree = create_nested_tree();
+
+        let visits = tree
+            .pre_order()
+            .map(|(id, _, depth, last)| (id, depth, last))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            visits,
+            vec![
+                (0, 0, true),
+                (1, 1, false),
+                (3, 2, false),
+                (4, 2, true),
+                (2, 1, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_post_order_and_breadth_first() {
+        let tree = create_nested_tree();
+
+        let post = tree.post_order().map(|v| v.0).collect::<Vec<_>>();
+        let breadth = tree.breadth_first().map(|v| v.0).collect::<Vec<_>>();
+
+        assert_eq!(post, vec![3, 4, 1, 2, 0]);
+        assert_eq!(breadth, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_relatives() {
+        let tree = create_nested_tree();
+
+        let ancestors = tree
+            .ancestors(4)
+            .map(|(id, _, depth, _)| (id, depth))
+            .collect::<Vec<_>>();
+        let siblings = tree.siblings(4).map(|v| v.0).collect::<Vec<_>>();
+        let descendants = tree.descendants(1).map(|v| v.0).collect::<Vec<_>>();
+
+        assert_eq!(ancestors, vec![(1, 1), (0, 0)]);
+        assert_eq!(siblings, vec![3]);
+        assert_eq!(descendants, vec![3, 4]);
+        assert_eq!(tree.siblings(0).count(), 0);
+    }
+
+    #[test]
+    fn test_post_order_mut() {
+        let mut tree = create_nested_tree();
+        let mut order = Vec::new();
+
+        tree.post_order_mut(1, |id, node, _, _| {
+            node.children.clear();
+            order.push(id);
+        });
+
+        assert_eq!(order, vec![3, 4, 1]);
+        assert!(tree.get_node(1).unwrap().children.is_empty());
+    }
 }
@@ -0,0 +1,438 @@
+//! Walks the file system for a [FileTree](super::file::FileTree).
+//!
+//! Walking produces a tree of [Node]s which is then inserted into the arena in pre-order. Because
+//! the arena is only touched once the walk is complete, directories can be walked on a thread pool
+//! and still produce exactly the same tree as a sequential walk.
+
+use std::collections::HashSet;
+use std::fs::{DirEntry, Metadata, ReadDir, read_dir};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::file::{FileMetadata, FileTreeOptions, FileType, SortKey, WalkError};
+use crate::tree::filter::Filters;
+#[cfg(feature = "ignore")]
+use crate::tree::ignores::Ignores;
+use crate::tree::metadata::Owners;
+use crate::tree::os_str_to_string;
+use crate::tree::sort::SortEntry;
+
+/// Identifies a directory regardless of the path it was reached by, so that symlink cycles can be
+/// detected
+#[cfg(unix)]
+type DirKey = (u64, u64);
+#[cfg(not(unix))]
+type DirKey = PathBuf;
+
+#[cfg(unix)]
+fn dir_key(_path: &Path, metadata: &Metadata) -> Option<DirKey> {
+    use std::os::unix::fs::MetadataExt;
+
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn dir_key(path: &Path, _metadata: &Metadata) -> Option<DirKey> {
+    path.canonicalize().ok()
+}
+
+/// A walked entry, along with the walked contents of a directory
+pub(crate) struct Node {
+    pub name: String,
+    pub path: PathBuf,
+    pub file_type: FileType,
+    pub metadata: Option<FileMetadata>,
+    pub error: Option<io::ErrorKind>,
+    pub children: Vec<Node>,
+    /// The directory was not walked because of the max depth, but has entries
+    pub truncated: bool,
+    /// Errors recorded while walking this entry, not including those of its children
+    pub errors: Vec<WalkError>,
+}
+
+/// The walked contents of a directory
+#[derive(Default)]
+pub(crate) struct Contents {
+    pub nodes: Vec<Node>,
+    /// The error which occurred while reading the directory
+    pub error: Option<io::ErrorKind>,
+    /// Errors recorded while reading the directory, not including those of its children
+    pub errors: Vec<WalkError>,
+}
+
+/// A directory entry which has been read but not yet walked
+struct Entry {
+    name: String,
+    path: PathBuf,
+    file_type: FileType,
+    metadata: Option<Metadata>,
+    error: Option<io::ErrorKind>,
+}
+
+/// The state of a walk through one branch of the file system
+#[derive(Clone)]
+pub(crate) struct Walker<'a> {
+    root: &'a Path,
+    options: &'a FileTreeOptions,
+    filters: Filters,
+    owners: &'a Owners,
+    /// Walk the children of each directory on the rayon thread pool
+    #[cfg(feature = "parallel")]
+    parallel: bool,
+    #[cfg(feature = "ignore")]
+    ignores: Ignores,
+    /// The directories currently being walked, used to detect symlink cycles
+    walking: HashSet<DirKey>,
+}
+
+impl<'a> Walker<'a> {
+    /// Create a walker for a tree rooted at the given path
+    pub fn new(
+        root: &'a Path,
+        options: &'a FileTreeOptions,
+        owners: &'a Owners,
+    ) -> io::Result<Self> {
+        let mut walking = HashSet::new();
+        if options.follow_links
+            && let Some(key) = root.metadata().ok().and_then(|m| dir_key(root, &m))
+        {
+            walking.insert(key);
+        }
+
+        Ok(Self {
+            root,
+            options,
+            filters: Filters::new(&options.include, &options.exclude)?,
+            owners,
+            #[cfg(feature = "parallel")]
+            parallel: false,
+            #[cfg(feature = "ignore")]
+            ignores: if options.ignore {
+                Ignores::new(root)
+            } else {
+                Ignores::default()
+            },
+            walking,
+        })
+    }
+
+    /// Walk the children of each directory in parallel
+    #[cfg(feature = "parallel")]
+    pub fn parallel(mut self) -> Self {
+        self.parallel = true;
+        self
+    }
+
+    /// Walk a directory, where `depth` is the depth of its children
+    pub fn walk(&mut self, path: &Path, depth: usize) -> io::Result<Contents> {
+        let dir = match read_dir(path) {
+            Ok(dir) => dir,
+            Err(e) => {
+                let mut contents = Contents::default();
+                contents.error = Some(self.tolerate(path, e, &mut contents.errors)?);
+                return Ok(contents);
+            }
+        };
+
+        self.enter_directory(path);
+        let result = self.walk_entries(path, dir, depth);
+        self.leave_directory();
+        result
+    }
+
+    fn walk_entries(&mut self, path: &Path, dir: ReadDir, depth: usize) -> io::Result<Contents> {
+        let mut contents = Contents::default();
+        let dir_metadata = path.metadata().ok();
+
+        let mut entries = Vec::new();
+        for entry in dir {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    contents.error = Some(self.tolerate(path, e, &mut contents.errors)?);
+                    continue;
+                }
+            };
+
+            if let Some(entry) =
+                self.read_entry(entry, dir_metadata.as_ref(), &mut contents.errors)?
+            {
+                entries.push(entry);
+            }
+        }
+
+        let order = self.options.sort;
+        if order.key != SortKey::None || order.directories_first {
+            entries.sort_by(|a, b| {
+                order.compare(
+                    &SortEntry {
+                        name: &a.name,
+                        metadata: a.metadata.as_ref(),
+                    },
+                    &SortEntry {
+                        name: &b.name,
+                        metadata: b.metadata.as_ref(),
+                    },
+                )
+            });
+        }
+
+        contents.nodes = self.walk_children(entries, depth)?;
+        Ok(contents)
+    }
+
+    #[cfg(feature = "parallel")]
+    fn walk_children(&mut self, entries: Vec<Entry>, depth: usize) -> io::Result<Vec<Node>> {
+        use rayon::prelude::*;
+
+        if !self.parallel {
+            return self.walk_children_sequential(entries, depth);
+        }
+
+        // Each directory gets its own copy of the ignore stack and walked directories. The order of
+        // the entries is kept when collecting.
+        let nodes = entries
+            .into_par_iter()
+            .map(|entry| match self.node(entry) {
+                (node, Some(metadata)) => self.clone().walk_directory(node, &metadata, depth),
+                (node, None) => Ok(Some(node)),
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        Ok(nodes.into_iter().flatten().collect())
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn walk_children(&mut self, entries: Vec<Entry>, depth: usize) -> io::Result<Vec<Node>> {
+        self.walk_children_sequential(entries, depth)
+    }
+
+    fn walk_children_sequential(
+        &mut self,
+        entries: Vec<Entry>,
+        depth: usize,
+    ) -> io::Result<Vec<Node>> {
+        let mut nodes = Vec::with_capacity(entries.len());
+        for entry in entries {
+            nodes.extend(self.walk_entry(entry, depth)?);
+        }
+
+        Ok(nodes)
+    }
+
+    /// Walk a single entry, descending into it if it is a directory. Returns [None] if the entry
+    /// is a directory which was pruned.
+    fn walk_entry(&mut self, entry: Entry, depth: usize) -> io::Result<Option<Node>> {
+        match self.node(entry) {
+            (node, Some(metadata)) => self.walk_directory(node, &metadata, depth),
+            (node, None) => Ok(Some(node)),
+        }
+    }
+
+    /// Create the node for an entry, along with its metadata if it is a directory
+    fn node(&self, entry: Entry) -> (Node, Option<Metadata>) {
+        let node = Node {
+            metadata: entry
+                .metadata
+                .as_ref()
+                .filter(|_| self.options.metadata)
+                .map(|m| FileMetadata::new(m, self.owners)),
+            name: entry.name,
+            path: entry.path,
+            file_type: entry.file_type,
+            error: entry.error,
+            children: Vec::new(),
+            truncated: false,
+            errors: Vec::new(),
+        };
+
+        (node, entry.metadata.filter(Metadata::is_dir))
+    }
+
+    /// Walk the contents of a directory node, or check for entries if it is past the max depth.
+    /// Returns [None] if the directory is empty and empty directories are pruned.
+    fn walk_directory(
+        &mut self,
+        mut node: Node,
+        metadata: &Metadata,
+        depth: usize,
+    ) -> io::Result<Option<Node>> {
+        let descends = self.options.descends(depth);
+        if descends {
+            if let Some(contents) = self.descend(&node.path, depth, metadata)? {
+                node.children = contents.nodes;
+                node.error = contents.error.or(node.error);
+                node.errors = contents.errors;
+            }
+        } else if self.options.show_truncated {
+            match self.has_entries(&node.path) {
+                Ok(found) => node.truncated = found,
+                Err(e) => node.error = Some(self.tolerate(&node.path, e, &mut node.errors)?),
+            }
+        }
+
+        // Directories past the max depth are only known to be empty if they were checked for
+        // entries, and directories which could not be read are kept so that the error is shown
+        let checked = descends || self.options.show_truncated;
+        let empty = checked && node.children.is_empty() && !node.truncated && node.error.is_none();
+        Ok((!(self.options.prune && empty)).then_some(node))
+    }
+
+    /// Walk a directory, skipping it if it is already being walked further up the tree
+    fn descend(
+        &mut self,
+        path: &Path,
+        depth: usize,
+        metadata: &Metadata,
+    ) -> io::Result<Option<Contents>> {
+        if !self.options.follow_links {
+            return self.walk(path, depth + 1).map(Some);
+        }
+
+        let key = dir_key(path, metadata);
+        if let Some(key) = &key {
+            if self.walking.contains(key) {
+                return Ok(None);
+            }
+            self.walking.insert(key.to_owned());
+        }
+
+        let result = self.walk(path, depth + 1);
+
+        if let Some(key) = key {
+            self.walking.remove(&key);
+        }
+        result.map(Some)
+    }
+
+    /// Returns if a directory contains any entries which would be included in the tree
+    fn has_entries(&mut self, path: &Path) -> io::Result<bool> {
+        let dir = read_dir(path)?;
+        self.enter_directory(path);
+
+        let found = dir
+            .map(|entry| {
+                let entry = entry?;
+                let name = entry.file_name();
+                Ok(self.options.includes(&name)
+                    && !self.leaves_out(
+                        &entry.path(),
+                        &name.to_string_lossy(),
+                        entry.file_type()?.is_dir(),
+                    ))
+            })
+            .find(|found| !matches!(found, Ok(false)))
+            .unwrap_or(Ok(false));
+
+        self.leave_directory();
+        found
+    }
+
+    /// Read a single directory entry, returning [None] if it should be left out of the tree
+    fn read_entry(
+        &self,
+        entry: DirEntry,
+        dir_metadata: Option<&Metadata>,
+        errors: &mut Vec<WalkError>,
+    ) -> io::Result<Option<Entry>> {
+        let name = entry.file_name();
+        if !self.options.includes(&name) {
+            return Ok(None);
+        }
+
+        let mut ret = Entry {
+            name: os_str_to_string(name),
+            path: entry.path(),
+            file_type: FileType::File,
+            metadata: None,
+            error: None,
+        };
+
+        let link_metadata = match entry.metadata() {
+            Ok(m) => m,
+            Err(e) => {
+                ret.error = Some(self.tolerate(&ret.path, e, errors)?);
+                return Ok((!self.leaves_out(&ret.path, &ret.name, false)).then_some(ret));
+            }
+        };
+
+        match FileType::from_metadata(&ret.path, &link_metadata, dir_metadata) {
+            Ok(t) => ret.file_type = t,
+            Err(e) => ret.error = Some(self.tolerate(&ret.path, e, errors)?),
+        }
+
+        // Followed links are described by their target, so that they sort and descend as
+        // directories
+        let metadata = match &ret.file_type {
+            FileType::Symlink { broken: false, .. } if self.options.follow_links => {
+                match ret.path.metadata() {
+                    Ok(m) => m,
+                    Err(e) => {
+                        ret.error = Some(self.tolerate(&ret.path, e, errors)?);
+                        link_metadata
+                    }
+                }
+            }
+            _ => link_metadata,
+        };
+
+        if self.leaves_out(&ret.path, &ret.name, metadata.is_dir()) {
+            return Ok(None);
+        }
+
+        ret.metadata = Some(metadata);
+        Ok(Some(ret))
+    }
+
+    /// Record an error while walking. In tolerant mode the error is kept and its kind returned so
+    /// that the walk can continue, otherwise it is returned as is.
+    fn tolerate(
+        &self,
+        path: &Path,
+        error: io::Error,
+        errors: &mut Vec<WalkError>,
+    ) -> io::Result<io::ErrorKind> {
+        if !self.options.tolerant {
+            return Err(error);
+        }
+
+        let kind = error.kind();
+        errors.push(WalkError {
+            path: path.into(),
+            error,
+        });
+        Ok(kind)
+    }
+
+    /// Read the ignore files of a directory before walking it
+    fn enter_directory(&mut self, _path: &Path) {
+        #[cfg(feature = "ignore")]
+        if self.options.ignore {
+            self.ignores.push(_path);
+        }
+    }
+
+    /// Forget the ignore files of the directory which was last entered
+    fn leave_directory(&mut self) {
+        #[cfg(feature = "ignore")]
+        if self.options.ignore {
+            self.ignores.pop();
+        }
+    }
+
+    /// Returns if an entry is ignored or filtered out by the include and exclude globs
+    fn leaves_out(&self, path: &Path, name: &str, is_dir: bool) -> bool {
+        let relative = path.strip_prefix(self.root).unwrap_or(path);
+        self.is_ignored(path, is_dir) || !self.filters.keeps(relative, name, is_dir)
+    }
+
+    #[cfg(feature = "ignore")]
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.options.ignore && self.ignores.is_ignored(path, is_dir)
+    }
+
+    #[cfg(not(feature = "ignore"))]
+    fn is_ignored(&self, _path: &Path, _is_dir: bool) -> bool {
+        false
+    }
+}
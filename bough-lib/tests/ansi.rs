@@ -0,0 +1,45 @@
+#![cfg(feature = "ansi")]
+mod common;
+
+use bough_lib::{
+    file::FileTree,
+    format::{
+        FormatConfig, TreeFormat,
+        ansi::{AnsiFormat, ColorMode},
+        text::TextFormat,
+    },
+};
+use common::Fixture;
+
+#[test]
+pub fn test_ansi_output() {
+    let fixture = Fixture::new("ansi", &["src/main.rs"]);
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let format = AnsiFormat {
+        mode: ColorMode::Always,
+        ..Default::default()
+    };
+
+    let output = format.to_string(&tree, &FormatConfig::default()).unwrap();
+
+    assert_eq!(
+        output,
+        "\x1b[2m└── \x1b[0m\x1b[34;1msrc\x1b[0m\n\x1b[2m    └── \x1b[0m\x1b[32mmain.rs\x1b[0m\n"
+    );
+}
+
+#[test]
+pub fn test_ansi_output_without_color() {
+    let fixture = Fixture::new("ansi-plain", &["src/main.rs"]);
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let config = FormatConfig::default();
+    let format = AnsiFormat {
+        mode: ColorMode::Never,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        format.to_string(&tree, &config).unwrap(),
+        TextFormat::default().to_string(&tree, &config).unwrap()
+    );
+}
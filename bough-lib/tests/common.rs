@@ -1 +1,44 @@
 //! This test module contains common code used between integration tests
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A temporary directory which is removed when dropped
+pub struct Fixture {
+    pub root: PathBuf,
+}
+
+impl Fixture {
+    /// Create a fixture directory containing the given entries. Entries ending in `/` are created
+    /// as directories, everything else as empty files.
+    pub fn new(name: &str, entries: &[&str]) -> Self {
+        let root = std::env::temp_dir().join(format!("bough-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+
+        for entry in entries {
+            let path = root.join(entry);
+            if entry.ends_with('/') {
+                fs::create_dir_all(&path).unwrap();
+            } else {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).unwrap();
+                }
+                fs::write(&path, "").unwrap();
+            }
+        }
+
+        Self { root }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
@@ -0,0 +1,346 @@
+mod common;
+
+use std::path::PathBuf;
+
+use bough_lib::{
+    config::{self, Config, Defaults},
+    file::FileTree,
+    output::icons::IconType,
+};
+use common::Fixture;
+
+#[test]
+pub fn test_apply_descriptions() {
+    let fixture = Fixture::new("config", &["src/main.rs", "docs/", "Cargo.toml"]);
+    let config: Config = "
+src/ The source code
+src/main.rs The entry point
+docs The docs, but as a file
+Cargo.toml/ The manifest, but as a directory
+missing.txt Does not exist
+"
+    .parse()
+    .unwrap();
+
+    let mut tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let unmatched = config
+        .apply(&mut tree)
+        .into_iter()
+        .map(|e| e.path.clone())
+        .collect::<Vec<_>>();
+    let description = |path| tree.get_by_relative_path(path).unwrap().description.clone();
+
+    assert_eq!(description("src").as_deref(), Some("The source code"));
+    assert_eq!(
+        description("src/main.rs").as_deref(),
+        Some("The entry point")
+    );
+    assert_eq!(description("docs"), None);
+    assert_eq!(description("Cargo.toml"), None);
+    assert_eq!(
+        unmatched,
+        vec![
+            PathBuf::from("docs"),
+            PathBuf::from("Cargo.toml"),
+            PathBuf::from("missing.txt")
+        ]
+    );
+}
+
+#[test]
+pub fn test_find_config() {
+    let fixture = Fixture::new("config-find", &["a.txt"]);
+    assert!(Config::find(fixture.path()).unwrap().is_none());
+
+    std::fs::write(
+        fixture.path().join(".boughconf"),
+        "a.txt A file\n---\ndepth=1\n",
+    )
+    .unwrap();
+    let config = Config::find(fixture.path()).unwrap().unwrap();
+
+    assert_eq!(config.entries.len(), 1);
+    assert_eq!(config.defaults.depth, Some(1));
+}
+
+#[test]
+pub fn test_generate() {
+    let fixture = Fixture::new("config-generate", &["src/main.rs", "docs/", "Cargo.toml"]);
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let defaults = Defaults {
+        depth: Some(2),
+        root: Some(true),
+        ..Default::default()
+    };
+
+    let generated = config::generate(&tree, &defaults, None).unwrap();
+    assert_eq!(
+        generated,
+        "Cargo.toml\ndocs/\nsrc/\nsrc/main.rs\n\n---\ndepth=2\nroot=true\n"
+    );
+}
+
+#[test]
+pub fn test_generate_merges() {
+    let fixture = Fixture::new("config-merge", &["src/main.rs", "Cargo.toml"]);
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let existing = "\
+# Keep this comment
+src/ The source code
+old.txt No longer exists
+# Fill this out later
+
+---
+# And this one
+depth=1
+icons=nerd
+";
+    let defaults = Defaults {
+        depth: Some(2),
+        root: Some(true),
+        ..Default::default()
+    };
+
+    let generated = config::generate(&tree, &defaults, Some(existing)).unwrap();
+    assert_eq!(
+        generated,
+        "\
+# Keep this comment
+src/ The source code
+old.txt No longer exists
+Cargo.toml
+src/main.rs
+# Fill this out later
+
+---
+# And this one
+depth=2
+icons=nerd
+root=true
+"
+    );
+
+    let config: Config = generated.parse().unwrap();
+    assert_eq!(config.entries.len(), 4);
+    assert_eq!(config.defaults.icons, Some(IconType::Nerd));
+}
+
+#[test]
+pub fn test_check() {
+    let fixture = Fixture::new("config-check", &["src/main.rs", "src/lib.rs", "Cargo.toml"]);
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let config: Config = "
+src/ The source code
+src/main.rs
+Cargo.toml The manifest
+src/ The source code, again
+old.rs Since renamed
+"
+    .parse()
+    .unwrap();
+
+    let problems = config
+        .check(&tree)
+        .iter()
+        .map(|p| p.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        problems,
+        vec![
+            "line 5: 'src/' was already given on line 2",
+            "line 6: 'old.rs' does not match anything in the tree",
+            "'src/lib.rs' has no description",
+            "'src/main.rs' has no description",
+        ]
+    );
+}
+
+#[test]
+pub fn test_apply_globs() {
+    let fixture = Fixture::new(
+        "config-globs",
+        &[
+            "src/tree/file.rs",
+            "src/tree/walk.rs",
+            "src/lib.rs",
+            "web/app.test.ts",
+            "web/app.ts",
+            "tests/",
+        ],
+    );
+    let config: Config = "
+src/tree/file.rs The file tree
+*.rs Rust source
+src/tree/*.rs Tree source
+src/tree/walk.rs
+*.test.ts Tests
+*.md Documentation
+t*/ Directories starting with t
+"
+    .parse()
+    .unwrap();
+
+    let mut tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let unmatched = config
+        .apply(&mut tree)
+        .into_iter()
+        .map(|e| e.line)
+        .collect::<Vec<_>>();
+    let description = |path| tree.get_by_relative_path(path).unwrap().description.clone();
+
+    // Exact paths win over globs, and later globs win over earlier ones
+    assert_eq!(
+        description("src/tree/file.rs").as_deref(),
+        Some("The file tree")
+    );
+    assert_eq!(
+        description("src/tree/walk.rs").as_deref(),
+        Some("Tree source")
+    );
+    assert_eq!(description("src/lib.rs").as_deref(), Some("Rust source"));
+    assert_eq!(description("web/app.test.ts").as_deref(), Some("Tests"));
+    assert_eq!(description("web/app.ts"), None);
+    assert_eq!(
+        description("src/tree").as_deref(),
+        Some("Directories starting with t")
+    );
+    assert_eq!(unmatched, vec![7]);
+}
+
+#[test]
+pub fn test_nested_configs() {
+    let fixture = Fixture::new(
+        "config-nested",
+        &[
+            "README.md",
+            "pkg/a/README.md",
+            "pkg/a/src/lib.rs",
+            "pkg/b.rs",
+        ],
+    );
+    let root: Config = "
+*.md Documentation
+README.md The readme
+pkg/ Packages
+"
+    .parse()
+    .unwrap();
+    let nested: Config = "
+README.md The readme of a
+src/lib.rs
+old.rs Since deleted
+"
+    .parse()
+    .unwrap();
+
+    let mut tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let dir = fixture.path().join("pkg/a");
+    root.apply(&mut tree);
+    let unmatched = nested
+        .apply_in(&mut tree, &dir)
+        .into_iter()
+        .map(|e| e.line)
+        .collect::<Vec<_>>();
+    let description = |path| tree.get_by_relative_path(path).unwrap().description.clone();
+
+    // The deeper configuration wins, and its paths are relative to its own directory
+    assert_eq!(description("README.md").as_deref(), Some("The readme"));
+    assert_eq!(
+        description("pkg/a/README.md").as_deref(),
+        Some("The readme of a")
+    );
+    assert_eq!(unmatched, vec![4]);
+
+    let problems = nested
+        .check_in(&tree, &dir)
+        .into_iter()
+        .chain(config::undescribed(&tree))
+        .map(|p| p.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        problems,
+        vec![
+            "line 4: 'old.rs' does not match anything in the tree",
+            "'pkg/a/' has no description",
+            "'pkg/a/src/' has no description",
+            "'pkg/a/src/lib.rs' has no description",
+            "'pkg/b.rs' has no description",
+        ]
+    );
+}
+
+#[test]
+pub fn test_single_level_globs() {
+    let fixture = Fixture::new(
+        "config-single-level",
+        &[
+            "src/lib.rs",
+            "src/main.rs",
+            "src/tree/file.rs",
+            "src/tree/walk.rs",
+        ],
+    );
+    let config: Config = "
+src/main.rs The entry point
+src/*.rs Top level source
+src/**/walk.rs Walking
+"
+    .parse()
+    .unwrap();
+
+    let mut tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    config.apply(&mut tree);
+    let description = |path| tree.get_by_relative_path(path).unwrap().description.clone();
+
+    assert_eq!(
+        description("src/lib.rs").as_deref(),
+        Some("Top level source")
+    );
+    // The exact path wins over the later glob
+    assert_eq!(
+        description("src/main.rs").as_deref(),
+        Some("The entry point")
+    );
+    // `*` does not match a `/`, so deeper files are not described
+    assert_eq!(description("src/tree/file.rs"), None);
+    assert_eq!(description("src/tree/walk.rs").as_deref(), Some("Walking"));
+}
+
+#[test]
+pub fn test_generate_without_defaults() {
+    let fixture = Fixture::new("config-generate-bare", &["a.txt"]);
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+
+    let generated = config::generate(&tree, &Defaults::default(), None).unwrap();
+    assert_eq!(generated, "a.txt\n");
+
+    let generated =
+        config::generate(&tree, &Defaults::default(), Some("a.txt A file\n---\n")).unwrap();
+    assert_eq!(generated, "a.txt A file\n\n---\n");
+}
+
+#[test]
+pub fn test_config_files_need_no_description() {
+    let fixture = Fixture::new(
+        "config-self",
+        &[".boughconf", "sub/.boughconf", "sub/a.txt"],
+    );
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .hidden(true)
+        .build()
+        .unwrap();
+    let config: Config = "sub/ A directory\n".parse().unwrap();
+
+    let problems = config
+        .check(&tree)
+        .iter()
+        .map(|p| p.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(problems, vec!["'sub/a.txt' has no description"]);
+    assert_eq!(config::undescribed(&tree).len(), 2);
+
+    let generated = config::generate(&tree, &Defaults::default(), None).unwrap();
+    assert_eq!(generated, "sub/\nsub/a.txt\n");
+}
@@ -0,0 +1,498 @@
+mod common;
+
+use bough_lib::TreeItem;
+use bough_lib::file::{FileTree, FileType, SortKey, SortOrder};
+use common::Fixture;
+
+fn names(tree: &FileTree) -> Vec<String> {
+    let mut names = tree
+        .tree()
+        .assigned_nodes()
+        .iter()
+        .map(|n| n.name.clone())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+#[test]
+pub fn test_max_depth() {
+    let fixture = Fixture::new("depth", &["a/b/c.txt", "d.txt"]);
+    let root = fixture.path().file_name().unwrap().to_string_lossy();
+
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .max_depth(Some(2))
+        .build()
+        .unwrap();
+
+    let mut expected = vec!["a", "b", "d.txt", &root];
+    expected.sort();
+    assert_eq!(names(&tree), expected);
+}
+
+#[test]
+pub fn test_hidden_entries() {
+    let fixture = Fixture::new("hidden", &[".git/HEAD", ".env", "visible.txt"]);
+
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    assert_eq!(tree.tree().len(), 2);
+
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .hidden(true)
+        .build()
+        .unwrap();
+    assert_eq!(tree.tree().len(), 5);
+}
+
+#[test]
+pub fn test_truncated_marker() {
+    let fixture = Fixture::new("truncated", &["a/b.txt", "empty/"]);
+
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .max_depth(Some(1))
+        .show_truncated(true)
+        .build()
+        .unwrap();
+
+    let markers = tree
+        .tree()
+        .assigned_nodes()
+        .into_iter()
+        .filter(|n| n.file_type == FileType::Truncated)
+        .collect::<Vec<_>>();
+
+    assert_eq!(markers.len(), 1);
+    assert_eq!(markers[0].path, fixture.path().join("a"));
+}
+
+#[cfg(feature = "ignore")]
+#[test]
+pub fn test_ignore_files() {
+    let fixture = Fixture::new(
+        "ignore",
+        &[
+            ".git/",
+            "target/debug/bough",
+            "src/main.rs",
+            "src/generated.rs",
+            "src/keep.log",
+            "other.log",
+            "notes.txt",
+        ],
+    );
+    std::fs::write(fixture.path().join(".gitignore"), "target/\n*.log\n").unwrap();
+    std::fs::write(fixture.path().join("src/.gitignore"), "!keep.log\n").unwrap();
+    std::fs::write(fixture.path().join(".boughignore"), "generated.rs\n").unwrap();
+    let root = fixture.path().file_name().unwrap().to_string_lossy();
+
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let mut expected = vec!["keep.log", "main.rs", "notes.txt", "src", &root];
+    expected.sort();
+    assert_eq!(names(&tree), expected);
+
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .ignore(false)
+        .build()
+        .unwrap();
+    assert_eq!(tree.tree().len(), 10);
+}
+
+fn child_names(tree: &FileTree) -> Vec<String> {
+    let arena = tree.tree();
+    arena
+        .get_node(arena.root)
+        .unwrap()
+        .children()
+        .iter()
+        .map(|id| arena.get_node(*id).unwrap().name.clone())
+        .collect()
+}
+
+#[test]
+pub fn test_sort_natural() {
+    let fixture = Fixture::new("sort", &["v10.txt", "v2.txt", "B.md", "a.rs", "dir/"]);
+
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    assert_eq!(
+        child_names(&tree),
+        vec!["B.md", "a.rs", "dir", "v2.txt", "v10.txt"]
+    );
+
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .sort(SortOrder {
+            directories_first: true,
+            case_insensitive: true,
+            reverse: true,
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+    assert_eq!(
+        child_names(&tree),
+        vec!["dir", "v10.txt", "v2.txt", "B.md", "a.rs"]
+    );
+}
+
+#[test]
+pub fn test_sort_extension() {
+    let fixture = Fixture::new("sort-ext", &["b.rs", "a.toml", "c.md", "Makefile"]);
+
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .sort(SortOrder {
+            key: SortKey::Extension,
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        child_names(&tree),
+        vec!["Makefile", "c.md", "b.rs", "a.toml"]
+    );
+}
+
+#[test]
+pub fn test_path_index() {
+    let fixture = Fixture::new("paths", &["src/tree/file.rs", "src/lib.rs"]);
+
+    let mut tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let id = tree.id_of(fixture.path().join("src/tree")).unwrap();
+
+    assert_eq!(
+        tree.get_by_relative_path("src/lib.rs").unwrap().name,
+        "lib.rs"
+    );
+    assert_eq!(
+        tree.get_by_path(fixture.path().join("src/tree/file.rs"))
+            .unwrap()
+            .name,
+        "file.rs"
+    );
+
+    let removed = tree.remove_subtree(id).unwrap();
+
+    assert_eq!(removed.len(), 2);
+    assert!(tree.get_by_relative_path("src/tree").is_none());
+    assert!(tree.get_by_relative_path("src/tree/file.rs").is_none());
+    assert!(tree.get_by_relative_path("src/lib.rs").is_some());
+}
+
+#[test]
+pub fn test_metadata() {
+    let fixture = Fixture::new("metadata", &["src/main.rs"]);
+    std::fs::write(fixture.path().join("src/main.rs"), "fn main() {}").unwrap();
+
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    assert!(
+        tree.get_by_relative_path("src/main.rs")
+            .unwrap()
+            .metadata
+            .is_none()
+    );
+
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .metadata(true)
+        .build()
+        .unwrap();
+    let metadata = tree
+        .get_by_relative_path("src/main.rs")
+        .unwrap()
+        .metadata
+        .clone()
+        .unwrap();
+
+    assert_eq!(metadata.size, 12);
+    assert!(metadata.modified.is_some());
+    #[cfg(unix)]
+    {
+        assert!(metadata.permissions_string().unwrap().starts_with("-rw"));
+        assert!(metadata.owner_string().is_some());
+        assert_eq!(metadata.links, Some(1));
+    }
+}
+
+#[cfg(unix)]
+#[test]
+pub fn test_symlinks() {
+    use std::os::unix::fs::symlink;
+
+    let fixture = Fixture::new("symlinks", &["dir/file.txt"]);
+    symlink("dir", fixture.path().join("link")).unwrap();
+    symlink("missing", fixture.path().join("broken")).unwrap();
+    // Points back at an ancestor, which would recurse forever if followed blindly
+    symlink("..", fixture.path().join("dir/loop")).unwrap();
+
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let link = tree.get_by_relative_path("link").unwrap();
+
+    assert_eq!(
+        link.file_type,
+        FileType::Symlink {
+            target: "dir".into(),
+            broken: false
+        }
+    );
+    assert!(link.children().is_empty());
+    assert_eq!(
+        tree.get_by_relative_path("broken").unwrap().file_type,
+        FileType::Symlink {
+            target: "missing".into(),
+            broken: true
+        }
+    );
+
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .follow_links(true)
+        .build()
+        .unwrap();
+
+    assert!(tree.get_by_relative_path("link/file.txt").is_some());
+    assert!(tree.get_by_relative_path("dir/loop").is_some());
+    assert!(tree.get_by_relative_path("dir/loop/dir").is_none());
+    assert!(tree.get_by_relative_path("link/loop").is_some());
+    assert!(tree.get_by_relative_path("link/loop/link").is_none());
+}
+
+#[cfg(unix)]
+#[test]
+pub fn test_special_file_types() {
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::UnixListener;
+
+    let fixture = Fixture::new("special", &["run.sh", "plain.txt"]);
+    let script = fixture.path().join("run.sh");
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let _socket = UnixListener::bind(fixture.path().join("socket")).unwrap();
+    let fifo = std::process::Command::new("mkfifo")
+        .arg(fixture.path().join("fifo"))
+        .status()
+        .is_ok_and(|s| s.success());
+
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let file_type = |p| tree.get_by_relative_path(p).unwrap().file_type.clone();
+
+    assert_eq!(file_type("run.sh"), FileType::Executable);
+    assert_eq!(file_type("plain.txt"), FileType::File);
+    assert_eq!(file_type("socket"), FileType::Socket);
+    if fifo {
+        assert_eq!(file_type("fifo"), FileType::Fifo);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+pub fn test_tolerant() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let fixture = Fixture::new("tolerant", &["locked/a.txt", "open/b.txt"]);
+    let locked = fixture.path().join("locked");
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+
+    // Permissions are not enforced for privileged users
+    if std::fs::read_dir(&locked).is_ok() {
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+        return;
+    }
+
+    assert!(FileTree::new(fixture.path()).unwrap().build().is_err());
+
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .tolerant(true)
+        .build()
+        .unwrap();
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    assert_eq!(
+        tree.get_by_relative_path("locked").unwrap().error,
+        Some(std::io::ErrorKind::PermissionDenied)
+    );
+    assert!(tree.get_by_relative_path("open/b.txt").is_some());
+    assert_eq!(tree.errors().len(), 1);
+    assert_eq!(tree.errors()[0].path, locked);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+pub fn test_parallel_matches_sequential() {
+    let mut entries = Vec::new();
+    for a in 0..8 {
+        for b in 0..8 {
+            entries.push(format!("dir{a}/sub{b}/file{a}{b}.txt"));
+            entries.push(format!("dir{a}/file{b}.rs"));
+        }
+        entries.push(format!("dir{a}/deep/er/still/file.md"));
+    }
+    let entries = entries.iter().map(String::as_str).collect::<Vec<_>>();
+    let fixture = Fixture::new("parallel", &entries);
+
+    let build = |parallel: bool| {
+        let tree = FileTree::new(fixture.path())
+            .unwrap()
+            .max_depth(Some(4))
+            .show_truncated(true)
+            .sort(SortOrder {
+                key: SortKey::Extension,
+                directories_first: true,
+                ..Default::default()
+            });
+        if parallel {
+            tree.build_parallel().unwrap()
+        } else {
+            tree.build().unwrap()
+        }
+    };
+    let items = |tree: &FileTree| {
+        tree.tree()
+            .pre_order()
+            .map(|(id, item, depth, _)| (id, item.clone(), depth))
+            .collect::<Vec<_>>()
+    };
+
+    let sequential = build(false);
+    let parallel = build(true);
+
+    assert_eq!(items(&sequential), items(&parallel));
+    assert_eq!(
+        sequential
+            .get_by_relative_path("dir3/sub5/file35.txt")
+            .unwrap()
+            .name,
+        "file35.txt"
+    );
+}
+
+#[test]
+pub fn test_include_exclude() {
+    let fixture = Fixture::new(
+        "globs",
+        &[
+            "Cargo.toml",
+            "Cargo.lock",
+            "src/main.rs",
+            "src/gen/out.rs",
+            "docs/guide.md",
+            "node_modules/dep/index.js",
+        ],
+    );
+    let has = |tree: &FileTree, path| tree.get_by_relative_path(path).is_some();
+
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .include(["*.rs", "Cargo.toml"])
+        .exclude(["node_modules/", "src/gen/*.rs"])
+        .build()
+        .unwrap();
+
+    assert!(has(&tree, "Cargo.toml"));
+    assert!(has(&tree, "src/main.rs"));
+    assert!(!has(&tree, "Cargo.lock"));
+    assert!(!has(&tree, "docs/guide.md"));
+    assert!(!has(&tree, "src/gen/out.rs"));
+    assert!(!has(&tree, "node_modules"));
+    // Directories are kept even if nothing inside of them matches
+    assert!(has(&tree, "docs"));
+    assert!(has(&tree, "src/gen"));
+
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .include(["*.rs", "Cargo.toml"])
+        .exclude(["node_modules/", "src/gen/*.rs"])
+        .prune(true)
+        .build()
+        .unwrap();
+
+    assert!(has(&tree, "src/main.rs"));
+    assert!(!has(&tree, "docs"));
+    assert!(!has(&tree, "src/gen"));
+
+    assert!(
+        FileTree::new(fixture.path())
+            .unwrap()
+            .include(["a["])
+            .build()
+            .is_err()
+    );
+}
+
+#[test]
+pub fn test_exclude_nested_paths() {
+    let fixture = Fixture::new("globs-nested", &["src/lib.rs", "src/tree/file.rs"]);
+    let has = |tree: &FileTree, path| tree.get_by_relative_path(path).is_some();
+
+    // `*` does not match a `/`, so only files directly inside of `src` are excluded
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .exclude(["src/*.rs"])
+        .build()
+        .unwrap();
+
+    assert!(!has(&tree, "src/lib.rs"));
+    assert!(has(&tree, "src/tree/file.rs"));
+}
+
+#[test]
+pub fn test_prune_past_max_depth() {
+    let fixture = Fixture::new("prune-depth", &["a/b.rs", "empty/"]);
+    let has = |tree: &FileTree, path| tree.get_by_relative_path(path).is_some();
+
+    // Directories at the max depth are not walked, so they are kept without knowing their contents
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .max_depth(Some(1))
+        .prune(true)
+        .build()
+        .unwrap();
+
+    assert!(has(&tree, "a"));
+    assert!(has(&tree, "empty"));
+
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .max_depth(Some(1))
+        .show_truncated(true)
+        .prune(true)
+        .build()
+        .unwrap();
+
+    assert!(has(&tree, "a"));
+    assert!(!has(&tree, "empty"));
+}
+
+#[test]
+pub fn test_summarize() {
+    let fixture = Fixture::new("summary", &["a/b/c.rs", "a/d.rs", "a/e.md", "f.txt"]);
+    std::fs::write(fixture.path().join("a/b/c.rs"), "12345").unwrap();
+    std::fs::write(fixture.path().join("f.txt"), "123").unwrap();
+
+    let mut tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    assert!(tree.summary().is_none());
+    tree.summarize();
+
+    let root = tree.summary().unwrap();
+    assert_eq!((root.directories, root.files, root.size), (2, 4, 8));
+    assert_eq!(root.extensions.get("rs"), Some(&2));
+
+    let a = tree
+        .get_by_relative_path("a")
+        .unwrap()
+        .summary
+        .clone()
+        .unwrap();
+    assert_eq!((a.directories, a.files, a.size), (1, 3, 5));
+    assert_eq!(a.to_string(), "1 directory, 3 files, 5");
+    assert!(
+        tree.get_by_relative_path("f.txt")
+            .unwrap()
+            .summary
+            .is_none()
+    );
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "html")]
+mod common;
+
+use bough_lib::{
+    file::FileTree,
+    format::{
+        FormatConfig, TreeFormat,
+        html::{DescriptionStyle, HtmlFormat},
+    },
+    output::icons::IconType,
+};
+use common::Fixture;
+
+#[test]
+pub fn test_html_output() {
+    let fixture = Fixture::new("html", &["src/main.rs"]);
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let config = FormatConfig {
+        icons: IconType::Emoji,
+        ..Default::default()
+    };
+
+    let output = HtmlFormat::default().to_string(&tree, &config).unwrap();
+
+    assert_eq!(
+        output,
+        "<ul class=\"bough-tree\">
+  <li class=\"bough-dir\"><details><summary><span class=\"bough-icon\">📁</span><span class=\"bough-name\">src</span></summary>
+    <ul>
+      <li class=\"bough-file bough-ext-rs\"><span class=\"bough-icon\">🦀</span><span class=\"bough-name\">main.rs</span></li>
+    </ul>
+  </details></li>
+</ul>
+"
+    );
+}
+
+#[test]
+pub fn test_html_document() {
+    let fixture = Fixture::new("html-doc", &["a.txt"]);
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let format = HtmlFormat {
+        document: true,
+        open: true,
+        descriptions: DescriptionStyle::Tooltip,
+    };
+
+    let output = format.to_string(&tree, &FormatConfig::default()).unwrap();
+
+    assert!(output.starts_with("<!DOCTYPE html>\n"));
+    assert!(output.contains("<style>"));
+    assert!(output.ends_with("</body>\n</html>\n"));
+}
@@ -0,0 +1,23 @@
+//! Tests which change the current directory, kept apart so that they run in their own process
+#![cfg(feature = "ignore")]
+
+mod common;
+
+use bough_lib::file::FileTree;
+use common::Fixture;
+
+#[test]
+pub fn test_parent_ignore_from_subdirectory() {
+    let fixture = Fixture::new("ignore-cwd", &[".git/", "pkg/secret/x", "pkg/y"]);
+    std::fs::write(fixture.path().join(".gitignore"), "/pkg/secret/\n").unwrap();
+    std::env::set_current_dir(fixture.path().join("pkg")).unwrap();
+
+    let tree = FileTree::new("./").unwrap().build().unwrap();
+    let names = tree
+        .tree()
+        .descendants(tree.tree().root)
+        .map(|(_, n, _, _)| n.name.clone())
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, vec!["y"]);
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "md")]
+mod common;
+
+use bough_lib::{
+    file::FileTree,
+    format::{
+        FormatConfig, TreeFormat,
+        md::{MarkdownFormat, MarkdownStyle},
+    },
+};
+use common::Fixture;
+
+#[test]
+pub fn test_markdown_code_block() {
+    let fixture = Fixture::new("md", &["src/main.rs"]);
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+
+    let output = MarkdownFormat::default()
+        .to_string(&tree, &FormatConfig::default())
+        .unwrap();
+
+    assert_eq!(output, "```\n└── src\n    └── main.rs\n```\n");
+}
+
+#[test]
+pub fn test_markdown_list_with_links() {
+    let fixture = Fixture::new("md-list", &["my src/main.rs"]);
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let format = MarkdownFormat {
+        style: MarkdownStyle::List,
+        links: true,
+        ..Default::default()
+    };
+
+    let output = format.to_string(&tree, &FormatConfig::default()).unwrap();
+
+    assert_eq!(
+        output,
+        "- [`my src`](my%20src)\n  - [`main.rs`](my%20src/main.rs)\n"
+    );
+}
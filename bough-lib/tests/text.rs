@@ -1,4 +1,151 @@
 mod common;
 
+use bough_lib::{
+    file::FileTree,
+    format::{
+        FormatConfig, TreeFormat,
+        text::{Section, TextFormat},
+    },
+    output::icons::IconType,
+};
+use common::Fixture;
+
+#[test]
+pub fn test_text_output() {
+    let fixture = Fixture::new("text", &["src/main.rs"]);
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+
+    let output = TextFormat::default()
+        .to_string(&tree, &FormatConfig::default())
+        .unwrap();
+
+    assert_eq!(output, "└── src\n    └── main.rs\n");
+}
+
+#[test]
+pub fn test_text_output_root_and_icons() {
+    let fixture = Fixture::new("text-root", &["src/main.rs"]);
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let config = FormatConfig {
+        icons: IconType::Emoji,
+        root: true,
+        ..Default::default()
+    };
+
+    let output = TextFormat::default().to_string(&tree, &config).unwrap();
+    let name = fixture.path().file_name().unwrap().to_string_lossy();
+
+    assert_eq!(
+        output,
+        format!("📁 {name}\n└── 📁 src\n    └── 🦀 main.rs\n")
+    );
+}
+
 #[test]
-pub fn test_text_output() {}
+pub fn test_text_render_to_writer() {
+    let fixture = Fixture::new("text-writer", &["a.txt"]);
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+
+    let mut out = Vec::new();
+    TextFormat::default()
+        .render(&tree, &FormatConfig::default(), &mut out)
+        .unwrap();
+
+    assert_eq!(out, "└── a.txt\n".as_bytes());
+}
+
+#[test]
+pub fn test_text_truncated() {
+    let fixture = Fixture::new("text-truncated", &["src/main.rs"]);
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .max_depth(Some(1))
+        .show_truncated(true)
+        .build()
+        .unwrap();
+
+    let output = TextFormat::default()
+        .to_string(&tree, &FormatConfig::default())
+        .unwrap();
+
+    assert_eq!(output, "└── src\n    └── …\n");
+}
+
+#[test]
+pub fn test_text_nested_guides() {
+    let fixture = Fixture::new("text-nested", &["a/b/c.txt", "a/d.txt", "e.txt"]);
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+
+    let output = TextFormat::default()
+        .to_string(&tree, &FormatConfig::default())
+        .unwrap();
+
+    assert_eq!(
+        output,
+        "├── a\n│   ├── b\n│   │   └── c.txt\n│   └── d.txt\n└── e.txt\n"
+    );
+}
+
+#[test]
+pub fn test_text_metadata_sections() {
+    let fixture = Fixture::new("text-metadata", &["a.txt"]);
+    std::fs::write(fixture.path().join("a.txt"), "hello").unwrap();
+    let tree = FileTree::new(fixture.path())
+        .unwrap()
+        .metadata(true)
+        .build()
+        .unwrap();
+
+    let format = TextFormat {
+        sections: vec![Section::Name, Section::Size, Section::Times],
+    };
+    let output = format.to_string(&tree, &FormatConfig::default()).unwrap();
+
+    assert_eq!(output, "└── a.txt [5] [just now]\n");
+}
+
+#[cfg(unix)]
+#[test]
+pub fn test_text_symlink() {
+    let fixture = Fixture::new("text-symlink", &["a.txt"]);
+    std::os::unix::fs::symlink("a.txt", fixture.path().join("b")).unwrap();
+    let tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+
+    let output = TextFormat::default()
+        .to_string(&tree, &FormatConfig::default())
+        .unwrap();
+
+    assert_eq!(output, "├── a.txt\n└── b -> a.txt\n");
+}
+
+#[test]
+pub fn test_text_error() {
+    let fixture = Fixture::new("text-error", &["locked/"]);
+    let mut tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    let id = tree.id_of(fixture.path().join("locked")).unwrap();
+    tree.get_mut(id).unwrap().error = Some(std::io::ErrorKind::PermissionDenied);
+
+    let output = TextFormat::default()
+        .to_string(&tree, &FormatConfig::default())
+        .unwrap();
+
+    assert_eq!(output, "└── locked [permission denied]\n");
+}
+
+#[test]
+pub fn test_text_contents_and_report() {
+    let fixture = Fixture::new("text-report", &["src/main.rs", "src/lib.rs", "README.md"]);
+    let mut tree = FileTree::new(fixture.path()).unwrap().build().unwrap();
+    tree.summarize();
+    let config = FormatConfig {
+        report: true,
+        ..Default::default()
+    };
+
+    let output = TextFormat::default().to_string(&tree, &config).unwrap();
+
+    assert_eq!(
+        output,
+        "├── README.md\n└── src [0 directories, 2 files, 0]\n    ├── lib.rs\n    └── main.rs\n\n1 directory, 3 files\n"
+    );
+}
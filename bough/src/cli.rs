@@ -1,22 +1,34 @@
-use std::{fmt::Display, path::PathBuf};
+use std::path::PathBuf;
 
+use bough_lib::config::{Defaults, OutputKind};
 use bough_lib::output::icons::IconType;
 
-use clap::{Command, CommandFactory, Parser, ValueEnum};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, Parser, Subcommand};
 use clap_help::Printer;
 use crossterm::style::Color;
 
-#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum OutputFormat {
-    HTML,
-    Text,
-    ANSI,
-    Markdown,
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Check that the .boughconf in [PATH] is up to date, failing if any entries no longer match
+    /// the tree, any items have no description, or any entries are given twice.
+    Check {
+        #[arg(default_value = "./", help = "The root path of the tree.")]
+        path: PathBuf,
+    },
 }
 
 #[derive(Parser, Debug, Clone, PartialEq, Eq, Hash)]
-#[command(author, version, about, disable_help_flag = true)]
+#[command(
+    author,
+    version,
+    about,
+    disable_help_flag = true,
+    disable_help_subcommand = true
+)]
 pub struct Args {
+    #[command(subcommand)]
+    pub action: Option<Action>,
     #[arg(
         long,
         short,
@@ -25,7 +37,7 @@ pub struct Args {
     )]
     pub depth: usize,
     #[arg(long, short, default_value = "text")]
-    pub format: OutputFormat,
+    pub format: OutputKind,
     #[arg(long, short, default_value_t = IconType::None, value_name="STYLE")]
     pub icons: IconType,
     #[arg(long, short, value_name = "PATH", help = "Output to the given file.")]
@@ -39,10 +51,74 @@ pub struct Args {
     pub copy: bool,
     #[arg(default_value = "./", help = "The root path of the tree.")]
     pub path: PathBuf,
-    #[arg(long = "all", short, help = "Display hidden files in the output.")]
+    #[arg(
+        long = "all",
+        short,
+        global = true,
+        help = "Display hidden files in the output."
+    )]
     pub all_files: bool,
+    #[arg(
+        long,
+        global = true,
+        help = "Include files matched by .gitignore, .ignore and .boughignore files."
+    )]
+    pub no_ignore: bool,
+    #[arg(
+        long,
+        short = 'l',
+        global = true,
+        help = "Follow symbolic links to directories, skipping links which lead to a cycle."
+    )]
+    pub follow_links: bool,
+    #[arg(
+        long,
+        short = 'P',
+        global = true,
+        value_name = "GLOB",
+        help = "Only show files matching the glob. Can be given more than once."
+    )]
+    pub include: Vec<String>,
+    #[arg(
+        long,
+        short = 'I',
+        global = true,
+        value_name = "GLOB",
+        help = "Leave out files and directories matching the glob. Can be given more than once."
+    )]
+    pub exclude: Vec<String>,
+    #[arg(
+        long,
+        global = true,
+        help = "Leave out directories which are empty after filtering."
+    )]
+    pub prune: bool,
+    #[arg(
+        long,
+        short,
+        help = "Show the total size and numbers of files and directories inside each directory."
+    )]
+    pub summary: bool,
+    #[arg(
+        long,
+        help = "Leave out the numbers of directories and files at the end of the tree."
+    )]
+    pub no_report: bool,
     #[arg(long, short, help = "Show the root folder at the top of the tree.")]
     pub root: bool,
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Read descriptions and defaults from the given file instead of [PATH]/.boughconf."
+    )]
+    pub config: Option<PathBuf>,
+    #[arg(
+        long,
+        global = true,
+        help = "Also read .boughconf files in subdirectories, describing the items inside of them."
+    )]
+    pub nested: bool,
     #[arg(long, short, help = "Print this help output.")]
     pub help: bool,
     #[arg(
@@ -53,6 +129,66 @@ pub struct Args {
     pub generate: bool,
 }
 
+impl Args {
+    /// Parse the arguments, keeping the matches so that it can be checked which were given
+    pub fn parse_with_matches() -> (Self, ArgMatches) {
+        let matches = Self::command().get_matches();
+        let args = Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        (args, matches)
+    }
+
+    /// The arguments given on the command line which can be given as defaults in a `.boughconf`.
+    /// Arguments which were not given are left unset, so that defaults from elsewhere are not
+    /// copied into the configuration.
+    pub fn defaults(&self, matches: &ArgMatches) -> Defaults {
+        let given = |id| given(matches, id);
+
+        Defaults {
+            output: given("format").then_some(self.format),
+            depth: given("depth").then_some(self.depth),
+            icons: given("icons").then_some(self.icons),
+            theme: None,
+            hidden: given("all_files").then_some(self.all_files),
+            ignore: given("no_ignore").then_some(!self.no_ignore),
+            root: given("root").then_some(self.root),
+            nested: given("nested").then_some(self.nested),
+        }
+    }
+
+    /// Fill in any arguments which were not given on the command line from the defaults of a
+    /// `.boughconf` and the user configuration
+    pub fn merge_defaults(&mut self, defaults: &Defaults, matches: &ArgMatches) {
+        let given = |id| given(matches, id);
+
+        if let Some(output) = defaults.output.filter(|_| !given("format")) {
+            self.format = output;
+        }
+        if let Some(depth) = defaults.depth.filter(|_| !given("depth")) {
+            self.depth = depth;
+        }
+        if let Some(icons) = defaults.icons.filter(|_| !given("icons")) {
+            self.icons = icons;
+        }
+        if let Some(hidden) = defaults.hidden.filter(|_| !given("all_files")) {
+            self.all_files = hidden;
+        }
+        if let Some(ignore) = defaults.ignore.filter(|_| !given("no_ignore")) {
+            self.no_ignore = !ignore;
+        }
+        if let Some(root) = defaults.root.filter(|_| !given("root")) {
+            self.root = root;
+        }
+        if let Some(nested) = defaults.nested.filter(|_| !given("nested")) {
+            self.nested = nested;
+        }
+    }
+}
+
+/// Returns if an argument was given on the command line, rather than taking its default value
+fn given(matches: &ArgMatches, id: &str) -> bool {
+    matches.value_source(id) == Some(ValueSource::CommandLine)
+}
+
 pub fn print_help_if_needed(args: &Args) -> bool {
     if args.help {
         build_help_printer(Args::command()).print_help();
@@ -91,11 +227,18 @@ struct Example<'a> {
     pub comments: &'a str,
 }
 
-static EXAMPLES: [Example<'static>; 1] = [Example {
-    title: "Build an HTML tree outputting it to a file",
-    cmd: "bough ./src -o output.html -i nerd",
-    comments: "This outputs an HTML tree based on `./src` with Nerd Font icons to `output.html`",
-}];
+static EXAMPLES: [Example<'static>; 2] = [
+    Example {
+        title: "Build an HTML tree outputting it to a file",
+        cmd: "bough ./src -o output.html -i nerd",
+        comments: "This outputs an HTML tree based on `./src` with Nerd Font icons to `output.html`",
+    },
+    Example {
+        title: "Check that the descriptions of a project are up to date",
+        cmd: "bough check ./",
+        comments: "This lists any problems with `./.boughconf`, exiting with an error if there are any",
+    },
+];
 
 fn build_help_printer(cmd: Command) -> Printer<'static> {
     let mut p = Printer::new(cmd)
@@ -1,18 +1,270 @@
-use clap::Parser;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
 
-use crate::cli::{print_help_if_needed, Args};
-use bough_lib::file::FileTree;
+use clap::ArgMatches;
+
+use crate::cli::{Action, Args, print_help_if_needed};
+use bough_lib::{
+    config::{self, CONFIG_FILE, Config, OutputKind},
+    file::{FileTree, FileTreeOptions},
+    format::{
+        self, FormatConfig, TreeFormat,
+        ansi::{AnsiFormat, ColorMode},
+        html::HtmlFormat,
+        md::MarkdownFormat,
+        text::TextFormat,
+    },
+};
 
 mod cli;
 
-fn main() {
-    let args = Args::parse();
+fn main() -> ExitCode {
+    let (mut args, matches) = Args::parse_with_matches();
 
     if print_help_if_needed(&args) {
-        return;
+        return ExitCode::SUCCESS;
+    }
+
+    match run(&mut args, &matches) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("bough: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &mut Args, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    if args.copy {
+        return Err("copying to the clipboard is not supported yet".into());
+    }
+
+    if let Some(Action::Check { path }) = &args.action {
+        args.path = path.clone();
+    }
+
+    let config = read_config(args)?;
+    let mut defaults = config
+        .as_ref()
+        .map(|c| c.defaults.clone())
+        .unwrap_or_default();
+    if let Some(user) = read_user_config()? {
+        defaults.fill(&user.defaults);
+    }
+    args.merge_defaults(&defaults, matches);
+
+    // Checking always walks the whole tree, as entries below the display depth are still valid
+    let depth = (args.depth != 0 && args.action.is_none()).then_some(args.depth);
+    let mut tree = FileTree::new(&args.path)
+        .and_then(|t| {
+            t.options(FileTreeOptions {
+                max_depth: depth,
+                hidden: args.all_files,
+                show_truncated: true,
+                ignore: !args.no_ignore,
+                follow_links: args.follow_links,
+                tolerant: true,
+                include: args.include.clone(),
+                exclude: args.exclude.clone(),
+                prune: args.prune,
+                ..Default::default()
+            })
+            .build()
+        })
+        .map_err(|e| format!("could not read {}: {e}", args.path.display()))?;
+
+    // Unreadable entries are marked in the output, but are also reported in case it is not being
+    // read directly
+    for error in tree.errors() {
+        eprintln!("bough: {error}");
+    }
+
+    let mut configs = Vec::new();
+    if let Some(config) = config {
+        configs.push(Loaded {
+            dir: args.path.clone(),
+            path: config_path(args),
+            config,
+        });
+    }
+    if args.nested {
+        configs.extend(find_nested(&tree)?);
+    }
+
+    // Parents come before their children, so the deepest description of each item wins
+    for loaded in &configs {
+        loaded.config.apply_in(&mut tree, &loaded.dir);
+    }
+
+    if args.action.is_some() {
+        return check(args, &configs, &tree);
+    }
+    if args.generate {
+        return generate(args, matches, &tree);
+    }
+
+    if args.summary {
+        tree.summarize();
     }
 
-    let tree = FileTree::new("./").unwrap().build().unwrap();
+    let config = FormatConfig {
+        icons: args.icons,
+        root: args.root,
+        report: !args.no_report,
+        ..Default::default()
+    };
 
-    println!("{:?}", tree);
+    match &args.out {
+        Some(path) => {
+            let file = File::create(path)
+                .map_err(|e| format!("could not create {}: {e}", path.display()))?;
+            let mut out = BufWriter::new(file);
+            formatter(args.format, true).render(&tree, &config, &mut out)?;
+            out.flush()?;
+        }
+        None => {
+            let mut out = BufWriter::new(io::stdout().lock());
+            let result = formatter(args.format, false)
+                .render(&tree, &config, &mut out)
+                .and_then(|_| out.flush().map_err(format::Error::from));
+
+            // Output being cut short by e.g. `head` is not an error
+            match result {
+                Err(e) if e.kind == format::ErrorKind::Io(io::ErrorKind::BrokenPipe) => {}
+                r => r?,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The path of the configuration given by `--config`, or the `.boughconf` in the root of the tree
+fn config_path(args: &Args) -> PathBuf {
+    match &args.config {
+        Some(path) => path.clone(),
+        None => args.path.join(CONFIG_FILE),
+    }
+}
+
+/// A configuration read for a directory of the tree
+struct Loaded {
+    /// The directory its paths are relative to
+    dir: PathBuf,
+    /// The file it was read from
+    path: PathBuf,
+    config: Config,
+}
+
+/// Read the configuration given by `--config`, or the `.boughconf` in the root of the tree
+fn read_config(args: &Args) -> Result<Option<Config>, String> {
+    let path = config_path(args);
+
+    if args.config.is_none() && !path.is_file() {
+        return Ok(None);
+    }
+
+    Config::read(&path)
+        .map(Some)
+        .map_err(|e| format!("could not read {}: {e}", path.display()))
+}
+
+/// Read the user configuration, if there is one
+fn read_user_config() -> Result<Option<Config>, String> {
+    Config::user().map_err(|e| {
+        let path = config::user_path().unwrap_or_default();
+        format!("could not read {}: {e}", path.display())
+    })
+}
+
+/// Read the `.boughconf` in each directory below the root of the tree, parents first
+fn find_nested(tree: &FileTree) -> Result<Vec<Loaded>, String> {
+    let mut configs = Vec::new();
+
+    for (_, item, _, _) in tree.tree().descendants(tree.tree().root) {
+        if !item.is_directory() {
+            continue;
+        }
+
+        let path = item.path.join(CONFIG_FILE);
+        match Config::find(&item.path) {
+            Ok(Some(config)) => configs.push(Loaded {
+                dir: item.path.clone(),
+                path,
+                config,
+            }),
+            Ok(None) => {}
+            Err(e) => return Err(format!("could not read {}: {e}", path.display())),
+        }
+    }
+
+    Ok(configs)
+}
+
+/// Write a configuration for the tree, merging it into the existing one if there is one
+fn generate(args: &Args, matches: &ArgMatches, tree: &FileTree) -> Result<(), Box<dyn Error>> {
+    let path = config_path(args);
+    let existing = match fs::read_to_string(&path) {
+        Ok(contents) => Some(contents),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(format!("could not read {}: {e}", path.display()).into()),
+    };
+
+    let contents = config::generate(tree, &args.defaults(matches), existing.as_deref())
+        .map_err(|e| format!("could not read {}: {e}", path.display()))?;
+    fs::write(&path, contents).map_err(|e| format!("could not write {}: {e}", path.display()))?;
+
+    Ok(())
+}
+
+/// Print any problems with the configurations of the tree, which must already have been applied
+/// to it, failing if there are any
+fn check(args: &Args, configs: &[Loaded], tree: &FileTree) -> Result<(), Box<dyn Error>> {
+    let root = config_path(args);
+    if configs.is_empty() {
+        return Err(format!("could not find {}", root.display()).into());
+    }
+
+    let mut count = 0;
+    for loaded in configs {
+        for problem in loaded.config.check_in(tree, &loaded.dir) {
+            println!("{}: {problem}", loaded.path.display());
+            count += 1;
+        }
+    }
+    // Items are described relative to the root, even if only nested configurations exist
+    for problem in config::undescribed(tree) {
+        println!("{}: {problem}", root.display());
+        count += 1;
+    }
+
+    match count {
+        0 => Ok(()),
+        1 => Err("found 1 problem".into()),
+        n => Err(format!("found {n} problems").into()),
+    }
+}
+
+/// Get the renderer for an output format. Colour is always used for ANSI output written to a
+/// file, as it was explicitly requested.
+fn formatter(format: OutputKind, to_file: bool) -> Box<dyn TreeFormat<FileTree, FormatConfig>> {
+    match format {
+        OutputKind::Text => Box::new(TextFormat::default()),
+        OutputKind::Ansi => Box::new(AnsiFormat {
+            mode: if to_file {
+                ColorMode::Always
+            } else {
+                ColorMode::Auto
+            },
+            ..Default::default()
+        }),
+        OutputKind::Html => Box::new(HtmlFormat {
+            document: true,
+            ..Default::default()
+        }),
+        OutputKind::Markdown => Box::new(MarkdownFormat::default()),
+    }
 }
@@ -0,0 +1,90 @@
+mod common;
+
+use std::fs;
+
+use common::{bough, project};
+
+#[test]
+pub fn test_check_ignores_depth() {
+    let root = project(
+        "check-depth",
+        &[
+            ("src/main.rs", ""),
+            (
+                ".boughconf",
+                "src/ Source\nsrc/main.rs Entry\n---\ndepth=1\n",
+            ),
+        ],
+    );
+
+    let output = bough(&["check", root.to_str().unwrap()], &root.join("home"));
+    let _ = fs::remove_dir_all(&root);
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+    assert!(output.status.success());
+}
+
+#[test]
+pub fn test_check_reports_problems() {
+    let root = project(
+        "check-problems",
+        &[("a.txt", ""), (".boughconf", "old.txt Gone\n")],
+    );
+
+    let output = bough(&["check", root.to_str().unwrap()], &root.join("home"));
+    let _ = fs::remove_dir_all(&root);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("line 1: 'old.txt' does not match anything in the tree"));
+    assert!(stdout.contains("'a.txt' has no description"));
+    assert!(!output.status.success());
+}
+
+#[test]
+pub fn test_check_nested_without_root() {
+    let root = project(
+        "check-nested",
+        &[
+            ("a/x.txt", ""),
+            ("a/.boughconf", "x.txt A file\n"),
+            ("b/y.txt", ""),
+        ],
+    );
+
+    let output = bough(
+        &["check", "--nested", root.to_str().unwrap()],
+        &root.join("home"),
+    );
+    let _ = fs::remove_dir_all(&root);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // Undescribed items are reported against the root, not the first nested configuration
+    let expected = root.join(".boughconf");
+    assert!(stdout.contains(&format!(
+        "{}: 'b/y.txt' has no description",
+        expected.display()
+    )));
+    assert!(!stdout.contains("a/.boughconf"));
+    assert!(!output.status.success());
+}
+
+#[test]
+pub fn test_check_all_files() {
+    let root = project(
+        "check-all",
+        &[
+            ("a.txt", ""),
+            (".env", ""),
+            (".boughconf", "a.txt A file\n"),
+        ],
+    );
+
+    let output = bough(&["check", "-a", root.to_str().unwrap()], &root.join("home"));
+    let _ = fs::remove_dir_all(&root);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // Hidden files are checked, but the configuration itself is never expected to be described
+    assert!(stdout.contains("'.env' has no description"));
+    assert!(!stdout.contains("'.boughconf'"));
+    assert!(!output.status.success());
+}
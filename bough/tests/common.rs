@@ -0,0 +1,30 @@
+//! This test module contains common code used between integration tests
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Create a project directory containing the given files, where each file is given with its
+/// contents
+pub fn project(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("bough-cli-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+
+    for (file, contents) in files {
+        let path = root.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    root
+}
+
+/// Run `bough` with the given arguments and user configuration directory
+pub fn bough(args: &[&str], config_home: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_bough"))
+        .args(args)
+        .env("XDG_CONFIG_HOME", config_home)
+        .output()
+        .unwrap()
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use std::fs;
+
+use common::{bough, project};
+
+#[test]
+pub fn test_generate_leaves_out_user_defaults() {
+    let root = project(
+        "generate-user",
+        &[
+            ("a.txt", ""),
+            ("home/bough/config", "---\nicons=nerd\nroot=true\n"),
+        ],
+    );
+    let home = root.join("home");
+
+    let output = bough(&["-g", "-d", "2", root.to_str().unwrap()], &home);
+    let generated = fs::read_to_string(root.join(".boughconf")).unwrap();
+    let _ = fs::remove_dir_all(&root);
+
+    assert!(output.status.success());
+    // Only the arguments given on the command line are written
+    assert!(generated.ends_with("---\ndepth=2\n"));
+    assert!(!generated.contains("icons"));
+    assert!(!generated.contains("root"));
+}